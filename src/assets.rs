@@ -0,0 +1,307 @@
+//! Resolves files referred to by a [`ShellDescript`] in its shell directory.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::ast::{LineContainer, ShellDescript, ShellDescriptLine, Span};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::thumbnail::resolve_thumbnail_path;
+
+/// What a file is referred to as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AssetKind {
+    MenuBackgroundBitmap,
+    MenuForegroundBitmap,
+    MenuSidebarBitmap,
+    Readme,
+    Thumbnail,
+}
+
+/// File referred to by a line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetReference {
+    kind: AssetKind,
+    name: String,
+    index: usize,
+    span: Option<Span>,
+    path: Option<PathBuf>,
+}
+
+impl ShellDescript {
+    /// Files referred to by the lines, in order of the lines, without resolving them.
+    pub fn assets(&self) -> Vec<AssetReference> {
+        self.lines()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| match line {
+                LineContainer::Body(v) => asset(v).map(|(kind, name)| AssetReference {
+                    kind,
+                    name: name.clone(),
+                    index,
+                    span: self.span(index).map(|v| v.value().unwrap_or(*v.line())),
+                    path: None,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Files referred to by the lines, each resolved in the shell directory `dir`.
+    ///
+    /// See [`resolve_asset_path`] for how the names are resolved,
+    /// and [`resolve_thumbnail_path`] for thumbnails.
+    pub fn resolve_assets<P: AsRef<Path>>(&self, dir: P) -> Vec<AssetReference> {
+        self.assets()
+            .into_iter()
+            .map(|mut v| {
+                v.path = match v.kind {
+                    AssetKind::Thumbnail => resolve_thumbnail_path(dir.as_ref(), &v.name),
+                    _ => resolve_asset_path(dir.as_ref(), &v.name),
+                };
+                v
+            })
+            .collect()
+    }
+
+    /// Reports files referred to by the lines which do not exist in the shell directory `dir`.
+    ///
+    /// Thumbnails are left to [`ShellDescript::check_thumbnails`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use shell_parser_descript_rs::load_shell_dir;
+    ///
+    /// let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/descript");
+    /// let shell_descript = load_shell_dir(&dir).unwrap();
+    ///
+    /// let result = shell_descript.check_assets(&dir);
+    /// assert_eq!(result[0].code(), "missing-asset");
+    /// assert_eq!(
+    ///     result[0].message(),
+    ///     "menu.background.bitmap.filename refers to menu_background.png which is not found"
+    /// );
+    /// ```
+    pub fn check_assets<P: AsRef<Path>>(&self, dir: P) -> Vec<Diagnostic> {
+        self.resolve_assets(dir)
+            .into_iter()
+            .filter(|v| v.kind != AssetKind::Thumbnail && v.path.is_none())
+            .map(|v| {
+                let key = match &self.lines()[v.index] {
+                    LineContainer::Body(line) => line.key(),
+                    _ => String::new(),
+                };
+                Diagnostic::new(
+                    Severity::Error,
+                    "missing-asset",
+                    format!("{} refers to {} which is not found", key, v.name),
+                    Some(v.index),
+                    v.span,
+                )
+            })
+            .collect()
+    }
+
+    /// Existing files referred to by the lines, sorted without duplicates,
+    /// e.g. to include in a package.
+    pub fn referenced_files<P: AsRef<Path>>(&self, dir: P) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .resolve_assets(dir)
+            .into_iter()
+            .filter_map(|v| v.path)
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+}
+
+/// Kind and name of the file the line refers to.
+fn asset(line: &ShellDescriptLine) -> Option<(AssetKind, &String)> {
+    match line {
+        ShellDescriptLine::MenuBackgroundBitmapFilename(v) => {
+            Some((AssetKind::MenuBackgroundBitmap, v))
+        }
+        ShellDescriptLine::MenuForegroundBitmapFilename(v) => {
+            Some((AssetKind::MenuForegroundBitmap, v))
+        }
+        ShellDescriptLine::MenuSidebarBitmapFilename(v) => Some((AssetKind::MenuSidebarBitmap, v)),
+        ShellDescriptLine::Readme(v) => Some((AssetKind::Readme, v)),
+        ShellDescriptLine::SakuraBindgroupName(v)
+        | ShellDescriptLine::KeroBindgroupName(v)
+        | ShellDescriptLine::CharBindgroupName(_, v) => v
+            .thumbnail_name()
+            .as_ref()
+            .map(|name| (AssetKind::Thumbnail, name)),
+        _ => None,
+    }
+}
+
+/// Finds the file `name` refers to in `dir`.
+///
+/// Both `\` and `/` separate directories, and each part of the path matches
+/// regardless of case when no exact match exists, as on Windows.
+/// The path found is spelled as the directory lists it, whether the file system
+/// ignores case or not.
+///
+/// Names which could point outside `dir`, i.e. absolute ones, ones with a drive
+/// such as `C:`, or ones with a `..` part, resolve to nothing.
+pub fn resolve_asset_path(dir: &Path, name: &str) -> Option<PathBuf> {
+    if name.starts_with(['\\', '/']) || name.contains(':') {
+        return None;
+    }
+    let mut path = dir.to_path_buf();
+    for part in name
+        .split(['\\', '/'])
+        .filter(|v| !v.is_empty() && *v != ".")
+    {
+        if part == ".." {
+            return None;
+        }
+        let names: Vec<String> = fs::read_dir(&path)
+            .ok()?
+            .filter_map(|v| v.ok()?.file_name().into_string().ok())
+            .collect();
+        let found = names
+            .iter()
+            .find(|v| *v == part)
+            .or_else(|| names.iter().find(|v| v.eq_ignore_ascii_case(part)))?;
+        path = path.join(found);
+    }
+    path.is_file().then_some(path)
+}
+
+impl AssetReference {
+    pub fn kind(&self) -> &AssetKind {
+        &self.kind
+    }
+    /// Name as written in the line.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    /// Index of the referring line in [`ShellDescript::lines`].
+    pub fn index(&self) -> &usize {
+        &self.index
+    }
+    /// Span of the name in the parsed input.
+    pub fn span(&self) -> &Option<Span> {
+        &self.span
+    }
+    /// Path of the file found, `None` when it is missing or not resolved yet.
+    pub fn path(&self) -> &Option<PathBuf> {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ast::Position;
+    use crate::parse::parse;
+    use crate::test_support::shell_dir;
+
+    const CASE: &str = r#"charset,UTF-8
+readme,readme.txt
+menu.background.bitmap.filename,Menu\Background.PNG
+menu.foreground.bitmap.filename,menu/foreground.png
+menu.sidebar.bitmap.filename,menu_sidebar.png
+sakura.bindgroup0.name,服,エプロン,apron.png
+sakura.bindgroup1.name,服,ドレス
+kero.bindgroup0.name,首,ネクタイ,readme.txt
+"#;
+
+    mod assets {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = parse(CASE).unwrap().assets();
+            let kinds: Vec<(AssetKind, &str)> = result
+                .iter()
+                .map(|v| (*v.kind(), v.name().as_str()))
+                .collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    (AssetKind::Readme, "readme.txt"),
+                    (AssetKind::MenuBackgroundBitmap, "Menu\\Background.PNG"),
+                    (AssetKind::MenuForegroundBitmap, "menu/foreground.png"),
+                    (AssetKind::MenuSidebarBitmap, "menu_sidebar.png"),
+                    (AssetKind::Thumbnail, "apron.png"),
+                    (AssetKind::Thumbnail, "readme.txt"),
+                ]
+            );
+            assert_eq!(result[0].index(), &1);
+            assert_eq!(
+                result[0].span(),
+                &Some(Span::new(Position::new(21, 2, 8), Position::new(31, 2, 18)))
+            );
+            assert!(result.iter().all(|v| v.path().is_none()));
+        }
+    }
+
+    mod resolve_assets {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let dir = shell_dir(
+                "assets",
+                &["readme.txt", "menu/background.png", "menu/Foreground.png"],
+            );
+            let descript = parse(CASE).unwrap();
+
+            let result = descript.resolve_assets(&dir);
+            assert_eq!(result[0].path(), &Some(dir.join("readme.txt")));
+            assert!(result[1].path().is_some());
+            assert!(result[2].path().is_some());
+            assert_eq!(result[3].path(), &None);
+
+            let result = descript.check_assets(&dir);
+            let indexes: Vec<usize> = result.iter().map(|v| v.index().unwrap()).collect();
+            assert_eq!(indexes, vec![4]);
+            assert_eq!(
+                result[0].message(),
+                "menu.sidebar.bitmap.filename refers to menu_sidebar.png which is not found"
+            );
+
+            let result = descript.referenced_files(&dir);
+            assert_eq!(result.len(), 3);
+            assert_eq!(result[2], dir.join("readme.txt"));
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod resolve_asset_path {
+        use super::*;
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let dir = shell_dir("asset-path", &["menu/background.png"]);
+            assert_eq!(resolve_asset_path(&dir, "menu"), None);
+            assert_eq!(resolve_asset_path(&dir, "menu\\sidebar.png"), None);
+            assert_eq!(resolve_asset_path(&dir, "other\\background.png"), None);
+            assert_eq!(
+                resolve_asset_path(&dir, ".\\MENU\\BACKGROUND.png"),
+                Some(dir.join("menu").join("background.png"))
+            );
+
+            let inner = dir.join("menu");
+            assert_eq!(resolve_asset_path(&inner, "..\\menu\\background.png"), None);
+            assert_eq!(resolve_asset_path(&inner, "./../menu/background.png"), None);
+            let absolute = inner.join("background.png");
+            assert_eq!(resolve_asset_path(&inner, absolute.to_str().unwrap()), None);
+            assert_eq!(resolve_asset_path(&inner, "\\background.png"), None);
+            assert_eq!(resolve_asset_path(&inner, "C:background.png"), None);
+            assert_eq!(resolve_asset_path(&inner, "C:\\background.png"), None);
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
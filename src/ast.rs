@@ -10,9 +10,13 @@ pub type ColorType = u8;
 pub type AnimationIdType = u32;
 
 /// Root of `ShellDescript`.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Two `ShellDescript`s are equal when their lines are equal, regardless of sources and spans.
+#[derive(Debug, Clone)]
 pub struct ShellDescript {
     lines: Vec<LineContainer>,
+    sources: Vec<Option<LineSource>>,
+    spans: Vec<Option<LineSpan>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,6 +24,60 @@ pub enum LineContainer {
     EmptyLine,
     CommentLine(String),
     Body(ShellDescriptLine),
+    /// A line whose key is known but whose value cannot be read.
+    Invalid {
+        key: String,
+        value: String,
+        reason: String,
+    },
+    /// A `key,value` line whose key is not known, with the value split on commas.
+    Unknown {
+        key: String,
+        values: Vec<String>,
+    },
+}
+
+/// Original text of a line, kept by [`parse_lossless`].
+///
+/// [`parse_lossless`]: crate::parse::parse_lossless
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineSource {
+    text: String,
+    newline: Newline,
+}
+
+/// Position in the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+/// Range in the input, from `start` to just before `end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+
+/// Spans of a line and of its key and value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineSpan {
+    line: Span,
+    key: Option<Span>,
+    value: Option<Span>,
+    newline: Span,
+}
+
+/// Terminator of a line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Newline {
+    CrLf,
+    Cr,
+    Lf,
+    /// The line is the last one and has no terminator.
+    Eof,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -199,12 +257,254 @@ pub enum BindMenuVisibility {
 
 impl ShellDescript {
     pub fn new(lines: Vec<LineContainer>) -> ShellDescript {
-        ShellDescript { lines }
+        let sources = vec![None; lines.len()];
+        let spans = vec![None; lines.len()];
+        ShellDescript {
+            lines,
+            sources,
+            spans,
+        }
+    }
+
+    /// Creates a `ShellDescript` which remembers the original text and the span of each line.
+    pub fn with_sources(lines: Vec<(LineContainer, LineSource)>) -> ShellDescript {
+        let mut start = Position::new(0, 1, 1);
+        let mut spans = Vec::with_capacity(lines.len());
+        for (line, source) in lines.iter() {
+            spans.push(Some(LineSpan::from_source(start, line, source)));
+            start = Position::new(
+                start.offset + source.text.len() + source.newline.as_str().len(),
+                start.line + 1,
+                1,
+            );
+        }
+        let (lines, sources) = lines.into_iter().map(|(l, s)| (l, Some(s))).unzip();
+
+        ShellDescript {
+            lines,
+            sources,
+            spans,
+        }
+    }
+
+    /// Drops the original text of each line, keeping the spans.
+    pub fn forget_sources(mut self) -> ShellDescript {
+        self.sources = vec![None; self.lines.len()];
+        self
     }
 
     pub fn lines(&self) -> &Vec<LineContainer> {
         &self.lines
     }
+
+    /// Original text of each line, `None` for lines not read by [`parse_lossless`].
+    ///
+    /// [`parse_lossless`]: crate::parse::parse_lossless
+    pub fn sources(&self) -> &Vec<Option<LineSource>> {
+        &self.sources
+    }
+
+    /// Span of each line in the parsed input, `None` for lines not read by the parser.
+    pub fn spans(&self) -> &Vec<Option<LineSpan>> {
+        &self.spans
+    }
+
+    /// Span of the line at `index`.
+    pub fn span(&self, index: usize) -> Option<&LineSpan> {
+        self.spans.get(index).and_then(|v| v.as_ref())
+    }
+
+    /// Replaces the line at `index`, returning the previous one.
+    ///
+    /// The new line is written from its value, and has no span.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn replace(&mut self, index: usize, line: LineContainer) -> LineContainer {
+        self.sources[index] = None;
+        self.spans[index] = None;
+        std::mem::replace(&mut self.lines[index], line)
+    }
+
+    /// Inserts `line` at `index`, shifting the following lines.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, line: LineContainer) {
+        self.lines.insert(index, line);
+        self.sources.insert(index, None);
+        self.spans.insert(index, None);
+    }
+
+    /// Appends `line` to the end.
+    pub fn push(&mut self, line: LineContainer) {
+        self.insert(self.lines.len(), line);
+    }
+
+    /// Removes the line at `index`, returning it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> LineContainer {
+        self.sources.remove(index);
+        self.spans.remove(index);
+        self.lines.remove(index)
+    }
+
+    /// Keeps only the lines for which `f` returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&LineContainer) -> bool,
+    {
+        let keeps: Vec<bool> = self.lines.iter().map(&mut f).collect();
+        let mut keep = keeps.iter();
+        self.lines.retain(|_| *keep.next().unwrap());
+        let mut keep = keeps.iter();
+        self.sources.retain(|_| *keep.next().unwrap());
+        let mut keep = keeps.iter();
+        self.spans.retain(|_| *keep.next().unwrap());
+    }
+
+    /// Index of the line containing the byte `offset` of the parsed input,
+    /// counting the terminator as a part of the line.
+    pub fn line_index_at(&self, offset: usize) -> Option<usize> {
+        self.spans.iter().position(|v| {
+            v.as_ref().is_some_and(|v| {
+                v.line.start.offset <= offset
+                    && (offset < v.newline.end.offset || offset == v.line.end.offset)
+            })
+        })
+    }
+}
+
+impl PartialEq for ShellDescript {
+    fn eq(&self, other: &Self) -> bool {
+        self.lines == other.lines
+    }
+}
+
+impl Position {
+    pub fn new(offset: usize, line: usize, column: usize) -> Position {
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    /// Byte offset from the start of the input.
+    pub fn offset(&self) -> &usize {
+        &self.offset
+    }
+    /// 1-based line number.
+    pub fn line(&self) -> &usize {
+        &self.line
+    }
+    /// 1-based column, counted in characters.
+    pub fn column(&self) -> &usize {
+        &self.column
+    }
+
+    fn advance(&self, text: &str) -> Position {
+        Position::new(
+            self.offset + text.len(),
+            self.line,
+            self.column + text.chars().count(),
+        )
+    }
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Span {
+        Span { start, end }
+    }
+
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+    pub fn end(&self) -> &Position {
+        &self.end
+    }
+}
+
+impl LineSpan {
+    pub fn new(line: Span, key: Option<Span>, value: Option<Span>, newline: Span) -> LineSpan {
+        LineSpan {
+            line,
+            key,
+            value,
+            newline,
+        }
+    }
+
+    fn from_source(start: Position, line: &LineContainer, source: &LineSource) -> LineSpan {
+        let text = source.text();
+        let end = start.advance(text);
+        let (key, value) = match (line, text.split_once(',')) {
+            (
+                LineContainer::Body(_)
+                | LineContainer::Invalid { .. }
+                | LineContainer::Unknown { .. },
+                Some((k, v)),
+            ) => {
+                let key_end = start.advance(k);
+                let value_start = key_end.advance(",");
+                (
+                    Some(Span::new(start, key_end)),
+                    Some(Span::new(value_start, value_start.advance(v))),
+                )
+            }
+            _ => (None, None),
+        };
+
+        let newline = Span::new(end, end.advance(source.newline().as_str()));
+
+        LineSpan::new(Span::new(start, end), key, value, newline)
+    }
+
+    /// Span of the line, without its terminator.
+    pub fn line(&self) -> &Span {
+        &self.line
+    }
+    /// Span of the key, before the first comma.
+    pub fn key(&self) -> &Option<Span> {
+        &self.key
+    }
+    /// Span of the value, after the first comma.
+    pub fn value(&self) -> &Option<Span> {
+        &self.value
+    }
+    /// Span of the terminator, empty for the last line without one.
+    pub fn newline(&self) -> &Span {
+        &self.newline
+    }
+}
+
+impl LineSource {
+    pub fn new(text: String, newline: Newline) -> LineSource {
+        LineSource { text, newline }
+    }
+
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+    pub fn newline(&self) -> &Newline {
+        &self.newline
+    }
+}
+
+impl Newline {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Newline::CrLf => "\r\n",
+            Newline::Cr => "\r",
+            Newline::Lf => "\n",
+            Newline::Eof => "",
+        }
+    }
 }
 
 impl BindGroupNameProps {
@@ -234,10 +534,31 @@ impl BindGroupNameProps {
     pub fn thumbnail_name(&self) -> &Option<String> {
         &self.thumbnail_name
     }
+
+    pub fn set_id(&mut self, id: AnimationIdType) {
+        self.id = id;
+    }
+    pub fn set_category(&mut self, category: String) {
+        self.category = category;
+    }
+    pub fn set_part_name(&mut self, part_name: String) {
+        self.part_name = part_name;
+    }
+    pub fn set_thumbnail_name(&mut self, thumbnail_name: Option<String>) {
+        self.thumbnail_name = thumbnail_name;
+    }
 }
 
 impl BindOption {
+    /// # Panics
+    ///
+    /// Panics if neither `is_mustselect` nor `is_multiple` is set,
+    /// as a `bindoptionN.group` line needs at least one option.
     pub fn new(id: u32, category: String, is_mustselect: bool, is_multiple: bool) -> BindOption {
+        assert!(
+            is_mustselect || is_multiple,
+            "bindoption needs mustselect or multiple"
+        );
         BindOption {
             id,
             category,
@@ -258,4 +579,31 @@ impl BindOption {
     pub fn is_multiple(&self) -> &bool {
         &self.is_multiple
     }
+
+    pub fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+    pub fn set_category(&mut self, category: String) {
+        self.category = category;
+    }
+    /// # Panics
+    ///
+    /// Panics if this unsets the last option, as in [`BindOption::new`].
+    pub fn set_is_mustselect(&mut self, is_mustselect: bool) {
+        assert!(
+            is_mustselect || self.is_multiple,
+            "bindoption needs mustselect or multiple"
+        );
+        self.is_mustselect = is_mustselect;
+    }
+    /// # Panics
+    ///
+    /// Panics if this unsets the last option, as in [`BindOption::new`].
+    pub fn set_is_multiple(&mut self, is_multiple: bool) {
+        assert!(
+            is_multiple || self.is_mustselect,
+            "bindoption needs mustselect or multiple"
+        );
+        self.is_multiple = is_multiple;
+    }
 }
@@ -0,0 +1,314 @@
+//! Compares bindgroup ids of a [`ShellDescript`] with the animations of surfaces.txt.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::{AnimationIdType, CharacterIdType, ShellDescript};
+use crate::parse::AnimationDefinition;
+use crate::resolve::ShellConfig;
+
+/// Result of comparing the ids used by a character with the animations of surfaces.txt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindAnimationReport {
+    scope: CharacterIdType,
+    missing: Vec<AnimationIdType>,
+    not_bind: Vec<AnimationIdType>,
+    unused: Vec<AnimationIdType>,
+}
+
+impl ShellDescript {
+    /// Compares the ids used by each character with `animations` scanned from surfaces.txt.
+    ///
+    /// See [`ShellConfig::check_bind_animations`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::{parse, scan_animations};
+    ///
+    /// let descript = r#"charset,UTF-8
+    /// sakura.bindgroup10.name,帽子,麦わら
+    /// sakura.bindgroup11.name,帽子,リボン
+    /// sakura.bindgroup10.addid,30
+    /// "#;
+    /// let surfaces = r#"charset,UTF-8
+    /// surface0
+    /// {
+    /// animation10.interval,bind
+    /// animation12.interval,bind
+    /// animation30.interval,bind
+    /// }
+    /// "#;
+    ///
+    /// let reports = parse(descript)
+    ///     .unwrap()
+    ///     .check_bind_animations(&scan_animations(surfaces));
+    /// assert_eq!(reports.len(), 1);
+    /// assert_eq!(reports[0].missing(), &vec![11]);
+    /// assert_eq!(reports[0].unused(), &vec![12]);
+    /// ```
+    pub fn check_bind_animations(
+        &self,
+        animations: &BTreeMap<AnimationIdType, AnimationDefinition>,
+    ) -> Vec<BindAnimationReport> {
+        self.resolve().check_bind_animations(animations)
+    }
+
+    /// Returns ids of bind animations in `animations` which no character uses.
+    ///
+    /// See [`ShellConfig::unused_bind_animations`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::{parse, scan_animations};
+    ///
+    /// let descript = r#"charset,UTF-8
+    /// sakura.bindgroup10.name,帽子,麦わら
+    /// kero.bindgroup10.addid,30
+    /// "#;
+    /// let surfaces = r#"charset,UTF-8
+    /// surface0
+    /// {
+    /// animation10.interval,bind
+    /// animation12.interval,bind
+    /// }
+    /// surface10
+    /// {
+    /// animation30.interval,bind
+    /// }
+    /// "#;
+    ///
+    /// let unused = parse(descript)
+    ///     .unwrap()
+    ///     .unused_bind_animations(&scan_animations(surfaces));
+    /// assert_eq!(unused, vec![12]);
+    /// ```
+    pub fn unused_bind_animations(
+        &self,
+        animations: &BTreeMap<AnimationIdType, AnimationDefinition>,
+    ) -> Vec<AnimationIdType> {
+        self.resolve().unused_bind_animations(animations)
+    }
+}
+
+impl ShellConfig {
+    /// Compares the ids used by each character with `animations` scanned from surfaces.txt.
+    ///
+    /// For each character having `bindgroupN.name` or `bindgroupN.addid` lines, reports
+    /// - ids of `bindgroupN.name` and `bindgroupN.addid` no animation has,
+    /// - ids of `bindgroupN.name` whose animations are not bind animations,
+    /// - bind animations the character does not use in the surface blocks defining
+    ///   the ones it uses.
+    ///
+    /// Any character may show any surface, so surface blocks defining none of the ids
+    /// a character uses are taken as another character's; their bind animations are not
+    /// reported for it. See [`ShellConfig::unused_bind_animations`] for the whole shell.
+    pub fn check_bind_animations(
+        &self,
+        animations: &BTreeMap<AnimationIdType, AnimationDefinition>,
+    ) -> Vec<BindAnimationReport> {
+        let mut reports = Vec::new();
+        for scope in self.scopes().into_iter() {
+            let settings = self.character(scope);
+            let config = settings.config();
+            if config.bindgroups().is_empty() && config.bindgroup_addids().is_empty() {
+                continue;
+            }
+            let groups: BTreeSet<AnimationIdType> = config.bindgroups().keys().copied().collect();
+            let used: BTreeSet<AnimationIdType> = groups
+                .iter()
+                .copied()
+                .chain(config.bindgroup_addids().values().flatten().copied())
+                .collect();
+
+            let surfaces: BTreeSet<&String> = used
+                .iter()
+                .filter_map(|v| animations.get(v))
+                .flat_map(|v| v.surfaces())
+                .collect();
+
+            reports.push(BindAnimationReport {
+                scope,
+                missing: used
+                    .iter()
+                    .filter(|v| !animations.contains_key(v))
+                    .copied()
+                    .collect(),
+                not_bind: groups
+                    .iter()
+                    .filter(|v| animations.get(v).is_some_and(|v| !v.is_bind()))
+                    .copied()
+                    .collect(),
+                unused: animations
+                    .values()
+                    .filter(|v| {
+                        v.is_bind()
+                            && !used.contains(v.id())
+                            && v.surfaces().iter().any(|v| surfaces.contains(v))
+                    })
+                    .map(|v| *v.id())
+                    .collect(),
+            });
+        }
+        reports
+    }
+
+    /// Returns ids of bind animations in `animations` which no character uses
+    /// in `bindgroupN.name` or `bindgroupN.addid`.
+    pub fn unused_bind_animations(
+        &self,
+        animations: &BTreeMap<AnimationIdType, AnimationDefinition>,
+    ) -> Vec<AnimationIdType> {
+        let used: BTreeSet<AnimationIdType> = self
+            .scopes()
+            .into_iter()
+            .flat_map(|scope| {
+                let settings = self.character(scope);
+                let config = settings.config();
+                config
+                    .bindgroups()
+                    .keys()
+                    .copied()
+                    .chain(config.bindgroup_addids().values().flatten().copied())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        animations
+            .values()
+            .filter(|v| v.is_bind() && !used.contains(v.id()))
+            .map(|v| *v.id())
+            .collect()
+    }
+}
+
+impl BindAnimationReport {
+    pub fn scope(&self) -> &CharacterIdType {
+        &self.scope
+    }
+    /// Ids used by the character which no animation has.
+    pub fn missing(&self) -> &Vec<AnimationIdType> {
+        &self.missing
+    }
+    /// Ids of `bindgroupN.name` whose animations are not bind animations.
+    pub fn not_bind(&self) -> &Vec<AnimationIdType> {
+        &self.not_bind
+    }
+    /// Ids of bind animations the character does not use, in the surface blocks
+    /// defining the ones it uses.
+    pub fn unused(&self) -> &Vec<AnimationIdType> {
+        &self.unused
+    }
+    /// Whether nothing is reported.
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.not_bind.is_empty() && self.unused.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::{parse, scan_animations};
+
+    const SURFACES: &str = r#"charset,UTF-8
+surface0
+{
+animation0.interval,bind
+animation1.interval,bind
+animation2.interval,sometimes
+animation5.interval,bind+runonce
+}
+surface10
+{
+animation20.interval,bind
+}
+"#;
+
+    mod check_bind_animations {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+sakura.bindgroup0.name,帽子,麦わら
+sakura.bindgroup1.name,帽子,リボン
+sakura.bindgroup1.addid,5
+kero.bindgroup20.name,首,ネクタイ
+"#;
+            let result = parse(case)
+                .unwrap()
+                .check_bind_animations(&scan_animations(SURFACES));
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].scope(), &0);
+            assert_eq!(result[1].scope(), &1);
+            assert!(result.iter().all(|v| v.is_ok()));
+
+            // Animations of surface0 are not kero's, even though kero does not use them.
+            let case = "charset,UTF-8\nsakura.bindgroup0.name,帽子,麦わら\nkero.bindgroup20.name,首,ネクタイ\n";
+            let result = parse(case)
+                .unwrap()
+                .check_bind_animations(&scan_animations(SURFACES));
+            assert_eq!(result[0].unused(), &vec![1, 5]);
+            assert_eq!(result[1].unused(), &vec![]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = r#"charset,UTF-8
+sakura.bindgroup0.name,帽子,麦わら
+sakura.bindgroup2.name,帽子,リボン
+sakura.bindgroup3.name,帽子,王冠
+sakura.bindgroup0.addid,4
+char2.bindgroup9.addid,20
+"#;
+            let result = parse(case)
+                .unwrap()
+                .check_bind_animations(&scan_animations(SURFACES));
+            assert_eq!(
+                result,
+                vec![
+                    BindAnimationReport {
+                        scope: 0,
+                        missing: vec![3, 4],
+                        not_bind: vec![2],
+                        unused: vec![1, 5],
+                    },
+                    BindAnimationReport {
+                        scope: 2,
+                        missing: vec![],
+                        not_bind: vec![],
+                        unused: vec![],
+                    },
+                ]
+            );
+            assert!(!result[0].is_ok());
+        }
+    }
+
+    mod unused_bind_animations {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+sakura.bindgroup0.name,帽子,麦わら
+sakura.bindgroup1.addid,5
+kero.bindgroup20.name,首,ネクタイ
+"#;
+            let result = parse(case)
+                .unwrap()
+                .unused_bind_animations(&scan_animations(SURFACES));
+            assert_eq!(result, vec![1]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let result = parse("charset,UTF-8\n")
+                .unwrap()
+                .unused_bind_animations(&scan_animations(SURFACES));
+            assert_eq!(result, vec![0, 1, 5, 20]);
+        }
+    }
+}
@@ -0,0 +1,488 @@
+//! Builds a [`ShellDescript`] from scratch.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AnimationIdType, BalloonPosition, BindGroupNameProps, BindMenuItem, BindMenuVisibility,
+    BindOption, CharacterIdType, ColorType, DesktopPositionType, FlagType, FontSizeType,
+    LineContainer, MenuPositionForegroundBackgroundBase, MenuPositionForegroundBackgroundRepeat,
+    MenuPositionSidebarBase, MenuPositionSidebarRepeat, ShellDescript, ShellDescriptLine,
+    SurfacePosition,
+};
+
+/// Fluent builder of a [`ShellDescript`].
+///
+/// Methods taking `scope` write `sakura.` for scope 0, `kero.` for scope 1
+/// and `charN.` for scope N.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_common_rs::charset::Charset;
+/// use shell_parser_descript_rs::ShellDescriptBuilder;
+///
+/// let descript = ShellDescriptBuilder::new()
+///     .menu_font("MS Gothic", 12)
+///     .charset(Charset::UTF8)
+///     .name("master")
+///     .shell()
+///     .default_x(0, 0)
+///     .default_x(2, -40)
+///     .build();
+/// assert_eq!(
+///     descript.to_string(),
+///     "charset,UTF-8\r\nname,master\r\ntype,shell\r\n\r\nsakura.defaultx,0\r\nchar2.defaultx,-40\r\n\r\nmenu.font.name,MS Gothic\r\nmenu.font.height,12\r\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShellDescriptBuilder {
+    lines: Vec<ShellDescriptLine>,
+}
+
+impl ShellDescriptBuilder {
+    pub fn new() -> ShellDescriptBuilder {
+        ShellDescriptBuilder::default()
+    }
+
+    /// Adds `line` as it is.
+    pub fn line(mut self, line: ShellDescriptLine) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    // base
+    /// `charset`.
+    pub fn charset(self, v: Charset) -> Self {
+        self.line(ShellDescriptLine::Charset(v))
+    }
+
+    /// `name`.
+    pub fn name(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Name(v.to_string()))
+    }
+
+    /// `id`.
+    pub fn id(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Id(v.to_string()))
+    }
+
+    /// `type,shell`.
+    pub fn shell(self) -> Self {
+        self.line(ShellDescriptLine::Type)
+    }
+
+    /// `craftman`.
+    pub fn craftman(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Craftman(v.to_string()))
+    }
+
+    /// `craftmanw`.
+    pub fn craftmanw(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Craftmanw(v.to_string()))
+    }
+
+    /// `craftmanurl`.
+    pub fn craftmanurl(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Craftmanurl(v.to_string()))
+    }
+
+    /// `homeurl`.
+    pub fn homeurl(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Homeurl(v.to_string()))
+    }
+
+    /// `readme`.
+    pub fn readme(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::Readme(v.to_string()))
+    }
+
+    /// `readme.charset`.
+    pub fn readme_charset(self, v: Charset) -> Self {
+        self.line(ShellDescriptLine::ReadmeCharset(v))
+    }
+
+    /// `menu,hidden`.
+    pub fn menu_hidden(self) -> Self {
+        self.line(ShellDescriptLine::MenuHidden)
+    }
+
+    /// `sakura.name`, `kero.name` or `charN.name` by `scope`.
+    pub fn character_name(self, scope: CharacterIdType, v: &str) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraName(v.to_string()),
+            1 => ShellDescriptLine::KeroName(v.to_string()),
+            _ => ShellDescriptLine::CharName(scope, v.to_string()),
+        })
+    }
+
+    /// `sakura.name2`.
+    pub fn sakura_name2(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::SakuraName2(v.to_string()))
+    }
+
+    // shell representation
+    /// `seriko.zorder`.
+    pub fn seriko_zorder(self, v: Vec<CharacterIdType>) -> Self {
+        self.line(ShellDescriptLine::SerikoZOrder(v))
+    }
+
+    /// `seriko.sticky-window`.
+    pub fn seriko_sticky_window(self, v: Vec<CharacterIdType>) -> Self {
+        self.line(ShellDescriptLine::SerikoStickyWindow(v))
+    }
+
+    /// `seriko.alignmenttodesktop`.
+    pub fn seriko_alignmenttodesktop(self, v: SurfacePosition) -> Self {
+        self.line(ShellDescriptLine::SerikoAlignmenttodesktop(v))
+    }
+
+    /// `seriko.alignmenttodesktop` of the character at `scope`.
+    pub fn character_alignmenttodesktop(self, scope: CharacterIdType, v: SurfacePosition) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraSerikoAlignmenttodesktop(v),
+            1 => ShellDescriptLine::KeroSerikoAlignmenttodesktop(v),
+            _ => ShellDescriptLine::CharSerikoAlignmenttodesktop(scope, v),
+        })
+    }
+
+    /// `defaultx` of the character at `scope`.
+    pub fn default_x(self, scope: CharacterIdType, v: DesktopPositionType) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraDefaultx(v),
+            1 => ShellDescriptLine::KeroDefaultx(v),
+            _ => ShellDescriptLine::CharDefaultx(scope, v),
+        })
+    }
+
+    /// `defaulty` of the character at `scope`.
+    pub fn default_y(self, scope: CharacterIdType, v: DesktopPositionType) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraDefaulty(v),
+            1 => ShellDescriptLine::KeroDefaulty(v),
+            _ => ShellDescriptLine::CharDefaulty(scope, v),
+        })
+    }
+
+    /// `defaultleft` of the character at `scope`.
+    pub fn default_left(self, scope: CharacterIdType, v: DesktopPositionType) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraDefaultleft(v),
+            1 => ShellDescriptLine::KeroDefaultleft(v),
+            _ => ShellDescriptLine::CharDefaultleft(scope, v),
+        })
+    }
+
+    /// `defaulttop` of the character at `scope`.
+    pub fn default_top(self, scope: CharacterIdType, v: DesktopPositionType) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraDefaulttop(v),
+            1 => ShellDescriptLine::KeroDefaulttop(v),
+            _ => ShellDescriptLine::CharDefaulttop(scope, v),
+        })
+    }
+
+    // balloon representation
+    /// `sakura.balloon.offsetx` and `sakura.balloon.offsety`.
+    pub fn sakura_balloon_offset(self, x: DesktopPositionType, y: DesktopPositionType) -> Self {
+        self.line(ShellDescriptLine::SakuraBalloonOffsetx(x))
+            .line(ShellDescriptLine::SakuraBalloonOffsety(y))
+    }
+
+    /// `sakura.balloon.alignment`.
+    pub fn sakura_balloon_alignment(self, v: BalloonPosition) -> Self {
+        self.line(ShellDescriptLine::SakuraBalloonAlignment(v))
+    }
+
+    /// `kero.balloon.offsetx` and `kero.balloon.offsety`.
+    pub fn kero_balloon_offset(self, x: DesktopPositionType, y: DesktopPositionType) -> Self {
+        self.line(ShellDescriptLine::KeroBalloonOffsetx(x))
+            .line(ShellDescriptLine::KeroBalloonOffsety(y))
+    }
+
+    /// `kero.balloon.alignment`.
+    pub fn kero_balloon_alignment(self, v: BalloonPosition) -> Self {
+        self.line(ShellDescriptLine::KeroBalloonAlignment(v))
+    }
+
+    /// `balloon.dontmove` of the character at `scope`.
+    pub fn balloon_dontmove(self, scope: CharacterIdType, v: FlagType) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraBalloonDontmove(v),
+            1 => ShellDescriptLine::KeroBalloonDontmove(v),
+            _ => ShellDescriptLine::CharBalloonDontmove(scope, v),
+        })
+    }
+
+    // menu
+    /// `menu.font.name` and `menu.font.height`.
+    pub fn menu_font(self, name: &str, height: FontSizeType) -> Self {
+        self.line(ShellDescriptLine::MenuFontName(name.to_string()))
+            .line(ShellDescriptLine::MenuFontHeight(height))
+    }
+
+    /// `menu.background.bitmap.filename`.
+    pub fn menu_background_bitmap(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::MenuBackgroundBitmapFilename(
+            v.to_string(),
+        ))
+    }
+
+    /// `menu.foreground.bitmap.filename`.
+    pub fn menu_foreground_bitmap(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::MenuForegroundBitmapFilename(
+            v.to_string(),
+        ))
+    }
+
+    /// `menu.sidebar.bitmap.filename`.
+    pub fn menu_sidebar_bitmap(self, v: &str) -> Self {
+        self.line(ShellDescriptLine::MenuSidebarBitmapFilename(v.to_string()))
+    }
+
+    /// `menu.background.font.color.r`, `.g` and `.b`.
+    pub fn menu_background_font_color(self, r: ColorType, g: ColorType, b: ColorType) -> Self {
+        self.line(ShellDescriptLine::MenuBackgroundFontColorR(r))
+            .line(ShellDescriptLine::MenuBackgroundFontColorG(g))
+            .line(ShellDescriptLine::MenuBackgroundFontColorB(b))
+    }
+
+    /// `menu.foreground.font.color.r`, `.g` and `.b`.
+    pub fn menu_foreground_font_color(self, r: ColorType, g: ColorType, b: ColorType) -> Self {
+        self.line(ShellDescriptLine::MenuForegroundFontColorR(r))
+            .line(ShellDescriptLine::MenuForegroundFontColorG(g))
+            .line(ShellDescriptLine::MenuForegroundFontColorB(b))
+    }
+
+    /// `menu.separator.color.r`, `.g` and `.b`.
+    pub fn menu_separator_color(self, r: ColorType, g: ColorType, b: ColorType) -> Self {
+        self.line(ShellDescriptLine::MenuSeparatorColorR(r))
+            .line(ShellDescriptLine::MenuSeparatorColorG(g))
+            .line(ShellDescriptLine::MenuSeparatorColorB(b))
+    }
+
+    /// `menu.frame.color.r`, `.g` and `.b`.
+    pub fn menu_frame_color(self, r: ColorType, g: ColorType, b: ColorType) -> Self {
+        self.line(ShellDescriptLine::MenuFrameColorR(r))
+            .line(ShellDescriptLine::MenuFrameColorG(g))
+            .line(ShellDescriptLine::MenuFrameColorB(b))
+    }
+
+    /// `menu.disable.font.color.r`, `.g` and `.b`.
+    pub fn menu_disable_font_color(self, r: ColorType, g: ColorType, b: ColorType) -> Self {
+        self.line(ShellDescriptLine::MenuDisableFontColorR(r))
+            .line(ShellDescriptLine::MenuDisableFontColorG(g))
+            .line(ShellDescriptLine::MenuDisableFontColorB(b))
+    }
+
+    /// `menu.background.alignment`.
+    pub fn menu_background_alignment(
+        self,
+        base: MenuPositionForegroundBackgroundBase,
+        repeat1: Option<MenuPositionForegroundBackgroundRepeat>,
+        repeat2: Option<MenuPositionForegroundBackgroundRepeat>,
+    ) -> Self {
+        self.line(ShellDescriptLine::MenuBackgroundAlignment(
+            base, repeat1, repeat2,
+        ))
+    }
+
+    /// `menu.foreground.alignment`.
+    pub fn menu_foreground_alignment(
+        self,
+        base: MenuPositionForegroundBackgroundBase,
+        repeat1: Option<MenuPositionForegroundBackgroundRepeat>,
+        repeat2: Option<MenuPositionForegroundBackgroundRepeat>,
+    ) -> Self {
+        self.line(ShellDescriptLine::MenuForegroundAlignment(
+            base, repeat1, repeat2,
+        ))
+    }
+
+    /// `menu.sidebar.alignment`.
+    pub fn menu_sidebar_alignment(
+        self,
+        base: MenuPositionSidebarBase,
+        repeat: Option<MenuPositionSidebarRepeat>,
+    ) -> Self {
+        self.line(ShellDescriptLine::MenuSidebarAlignment(base, repeat))
+    }
+
+    // binding
+    /// `bindgroupN.name` of the character at `scope`.
+    pub fn bindgroup(self, scope: CharacterIdType, v: BindGroupNameProps) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraBindgroupName(v),
+            1 => ShellDescriptLine::KeroBindgroupName(v),
+            _ => ShellDescriptLine::CharBindgroupName(scope, v),
+        })
+    }
+
+    /// `bindgroupN.default` of the character at `scope`.
+    pub fn bindgroup_default(
+        self,
+        scope: CharacterIdType,
+        id: AnimationIdType,
+        v: FlagType,
+    ) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraBindgroupDefault(id, v),
+            1 => ShellDescriptLine::KeroBindgroupDefault(id, v),
+            _ => ShellDescriptLine::CharBindgroupDefault(scope, id, v),
+        })
+    }
+
+    /// `bindgroupN.addid` of the character at `scope`.
+    pub fn bindgroup_addid(
+        self,
+        scope: CharacterIdType,
+        id: AnimationIdType,
+        v: Vec<AnimationIdType>,
+    ) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraBindgroupAddid(id, v),
+            1 => ShellDescriptLine::KeroBindgroupAddid(id, v),
+            _ => ShellDescriptLine::CharBindgroupAddid(scope, id, v),
+        })
+    }
+
+    /// `bindoptionN.group` of the character at `scope`.
+    pub fn bindoption(self, scope: CharacterIdType, v: BindOption) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraBindoptionGroup(v),
+            1 => ShellDescriptLine::KeroBindoptionGroup(v),
+            _ => ShellDescriptLine::CharBindoptionGroup(scope, v),
+        })
+    }
+
+    /// `menuitemN` of the character at `scope`.
+    pub fn menuitem(self, scope: CharacterIdType, index: u32, v: BindMenuItem) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraMenuitem(index, v),
+            1 => ShellDescriptLine::KeroMenuitem(index, v),
+            _ => ShellDescriptLine::CharMenuitem(scope, index, v),
+        })
+    }
+
+    /// `menuitemexN` of the character at `scope`.
+    pub fn menuitemex(
+        self,
+        scope: CharacterIdType,
+        index: u32,
+        name: &str,
+        v: BindMenuItem,
+    ) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraMenuitemex(index, name.to_string(), v),
+            1 => ShellDescriptLine::KeroMenuitemex(index, name.to_string(), v),
+            _ => ShellDescriptLine::CharMenuitemex(scope, index, name.to_string(), v),
+        })
+    }
+
+    /// `menu` of the character at `scope`.
+    pub fn bind_menu(self, scope: CharacterIdType, v: BindMenuVisibility) -> Self {
+        self.line(match scope {
+            0 => ShellDescriptLine::SakuraMenu(v),
+            1 => ShellDescriptLine::KeroMenu(v),
+            _ => ShellDescriptLine::CharMenu(scope, v),
+        })
+    }
+
+    // alpha
+    /// `seriko.paint_transparent_region_black`.
+    pub fn paint_transparent_region_black(self, v: FlagType) -> Self {
+        self.line(ShellDescriptLine::SerikoPaintTransparentRegionBlack(v))
+    }
+
+    /// `seriko.use_self_alpha`.
+    pub fn use_self_alpha(self, v: FlagType) -> Self {
+        self.line(ShellDescriptLine::SerikoUseSelfAlpha(v))
+    }
+
+    /// Builds the lines grouped into sections separated by a blank line.
+    ///
+    /// `charset` comes first, and the other lines keep the order they were added in
+    /// within each section.
+    pub fn build(self) -> ShellDescript {
+        let mut lines = self.lines;
+        lines.sort_by_key(|v| (v.section(), !matches!(v, ShellDescriptLine::Charset(_))));
+
+        let mut result: Vec<LineContainer> = Vec::new();
+        let mut section = None;
+        for line in lines.into_iter() {
+            let current = Some(line.section());
+            if section.is_some() && section != current {
+                result.push(LineContainer::EmptyLine);
+            }
+            section = current;
+            result.push(LineContainer::Body(line));
+        }
+        ShellDescript::new(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::parse;
+
+    mod build {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = ShellDescriptBuilder::new()
+                .use_self_alpha(1)
+                .bindgroup(
+                    0,
+                    BindGroupNameProps::new(0, "帽子".to_string(), "麦わら".to_string(), None),
+                )
+                .bindgroup_default(0, 0, 1)
+                .menuitem(0, 0, BindMenuItem::Id(0))
+                .menuitemex(3, 1, "帽子", BindMenuItem::Line)
+                .menu_frame_color(10, 20, 30)
+                .sakura_balloon_offset(0, 80)
+                .balloon_dontmove(2, 1)
+                .character_name(0, "さくら")
+                .character_name(1, "うにゅう")
+                .character_name(2, "third")
+                .charset(Charset::UTF8)
+                .shell()
+                .build();
+            let expected = r#"charset,UTF-8
+sakura.name,さくら
+kero.name,うにゅう
+char2.name,third
+type,shell
+
+sakura.balloon.offsetx,0
+sakura.balloon.offsety,80
+char2.balloon.dontmove,1
+
+menu.frame.color.r,10
+menu.frame.color.g,20
+menu.frame.color.b,30
+
+sakura.bindgroup0.name,帽子,麦わら
+sakura.bindgroup0.default,1
+sakura.menuitem0,0
+char3.menuitemex1,帽子,-
+
+seriko.use_self_alpha,1
+"#
+            .replace('\n', "\r\n");
+            assert_eq!(result.to_string(), expected);
+            assert_eq!(parse(&expected).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_empty() {
+            let result = ShellDescriptBuilder::new().build();
+            assert_eq!(result, ShellDescript::new(vec![]));
+        }
+    }
+}
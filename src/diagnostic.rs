@@ -0,0 +1,132 @@
+//! Diagnostics reported about a [`ShellDescript`].
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use std::fmt;
+
+use crate::ast::Span;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// Problem found in a [`ShellDescript`].
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: String,
+    message: String,
+    index: Option<usize>,
+    span: Option<Span>,
+}
+
+impl Diagnostic {
+    pub fn new(
+        severity: Severity,
+        code: &str,
+        message: String,
+        index: Option<usize>,
+        span: Option<Span>,
+    ) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code: code.to_string(),
+            message,
+            index,
+            span,
+        }
+    }
+
+    pub fn severity(&self) -> &Severity {
+        &self.severity
+    }
+    /// Short identifier of the kind of problem, e.g. `missing-charset`.
+    pub fn code(&self) -> &String {
+        &self.code
+    }
+    pub fn message(&self) -> &String {
+        &self.message
+    }
+    /// Index in [`ShellDescript::lines`] of the line the problem is found at, if any.
+    ///
+    /// Unlike [`Position::line`], this counts from 0.
+    ///
+    /// [`ShellDescript::lines`]: crate::ast::ShellDescript::lines
+    /// [`Position::line`]: crate::ast::Position::line
+    pub fn index(&self) -> &Option<usize> {
+        &self.index
+    }
+    /// Span in the parsed input, if the line was read by the parser.
+    pub fn span(&self) -> &Option<Span> {
+        &self.span
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.severity, self.code)?;
+        if let Some(span) = &self.span {
+            write!(
+                f,
+                " line {}, column {}",
+                span.start().line(),
+                span.start().column()
+            )?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ast::Position;
+
+    mod fmt {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = Diagnostic::new(
+                Severity::Warning,
+                "charset-not-first",
+                "charset should be on the first line".to_string(),
+                Some(2),
+                Some(Span::new(Position::new(10, 3, 1), Position::new(23, 3, 14))),
+            );
+            assert_eq!(
+                case.to_string(),
+                "warning[charset-not-first] line 3, column 1: charset should be on the first line"
+            );
+
+            let case = Diagnostic::new(
+                Severity::Error,
+                "missing-charset",
+                "charset is not given".to_string(),
+                None,
+                None,
+            );
+            assert_eq!(
+                case.to_string(),
+                "error[missing-charset]: charset is not given"
+            );
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! Assembles the `bindgroup` and `bindoption` lines of a character into dressup categories.
+
+use std::{collections::BTreeSet, error::Error, fmt};
+
+use crate::ast::{AnimationIdType, CharacterIdType, ShellDescript};
+use crate::resolve::{CharacterConfig, ShellConfig};
+
+/// Dressup of a character.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dressup {
+    categories: Vec<DressupCategory>,
+}
+
+/// Category of dressup parts, e.g. `帽子`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DressupCategory {
+    name: String,
+    parts: Vec<DressupPart>,
+    is_mustselect: bool,
+    is_multiple: bool,
+}
+
+/// Part of a [`DressupCategory`], given with `bindgroupN.name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DressupPart {
+    id: AnimationIdType,
+    part_name: String,
+    thumbnail_name: Option<String>,
+    is_default: bool,
+    addids: Vec<AnimationIdType>,
+}
+
+/// Line which the dressup leaves out, as it refers to something undefined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DressupError {
+    /// `bindgroupN.default` for undefined `N`.
+    UndefinedDefault {
+        scope: CharacterIdType,
+        id: AnimationIdType,
+    },
+    /// `bindgroupN.addid` for undefined `N`.
+    UndefinedAddid {
+        scope: CharacterIdType,
+        id: AnimationIdType,
+    },
+    /// `bindoptionN.group` for a category which no `bindgroupN.name` has.
+    UnmatchedOption {
+        scope: CharacterIdType,
+        id: u32,
+        category: String,
+    },
+}
+
+/// Parts selected in a [`Dressup`], changed as the user toggles them in the baseware.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DressupState<'a> {
+    dressup: &'a Dressup,
+    active: BTreeSet<AnimationIdType>,
+}
+
+impl ShellDescript {
+    /// Resolves the dressup of the character at `scope`.
+    ///
+    /// Lines referring to something undefined are returned as errors beside the dressup.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::parse;
+    ///
+    /// let case = r#"charset,UTF-8
+    /// sakura.bindgroup0.name,帽子,麦わら,straw
+    /// sakura.bindgroup1.name,帽子,リボン
+    /// sakura.bindgroup2.name,服,エプロン
+    /// sakura.bindgroup1.default,1
+    /// sakura.bindgroup2.addid,10,11
+    /// sakura.bindoption0.group,帽子,mustselect
+    /// "#;
+    ///
+    /// let dressup = parse(case).unwrap().dressup(0).0;
+    /// let hat = dressup.category("帽子").unwrap();
+    /// assert!(hat.is_mustselect());
+    /// assert_eq!(hat.parts().len(), 2);
+    /// assert!(hat.parts()[1].is_default());
+    /// assert_eq!(dressup.part(2).unwrap().addids(), &vec![10, 11]);
+    /// ```
+    pub fn dressup(&self, scope: CharacterIdType) -> (Dressup, Vec<DressupError>) {
+        self.resolve().dressup(scope)
+    }
+}
+
+impl ShellConfig {
+    /// Resolves the dressup of the character at `scope`.
+    ///
+    /// See [`ShellConfig::character`] for how `sakura.`, `kero.` and `charN.` are merged.
+    pub fn dressup(&self, scope: CharacterIdType) -> (Dressup, Vec<DressupError>) {
+        Dressup::from_config(scope, self.character(scope).config())
+    }
+}
+
+impl Dressup {
+    /// Assembles the dressup from the settings of the character at `scope`.
+    ///
+    /// Categories come in order of their smallest id, and parts in order of id.
+    /// Options given more than once for a category are combined.
+    ///
+    /// Lines referring to something undefined are left out and returned as errors,
+    /// while the rest of the dressup is still assembled as the baseware does.
+    pub fn from_config(
+        scope: CharacterIdType,
+        config: &CharacterConfig,
+    ) -> (Dressup, Vec<DressupError>) {
+        let mut errors = Vec::new();
+        for id in config.bindgroup_defaults().keys() {
+            if !config.bindgroups().contains_key(id) {
+                errors.push(DressupError::UndefinedDefault { scope, id: *id });
+            }
+        }
+        for id in config.bindgroup_addids().keys() {
+            if !config.bindgroups().contains_key(id) {
+                errors.push(DressupError::UndefinedAddid { scope, id: *id });
+            }
+        }
+
+        let mut categories: Vec<DressupCategory> = Vec::new();
+        for (id, props) in config.bindgroups().iter() {
+            let part = DressupPart {
+                id: *id,
+                part_name: props.part_name().clone(),
+                thumbnail_name: props.thumbnail_name().clone(),
+                is_default: config.bindgroup_defaults().get(id).is_some_and(|v| *v != 0),
+                addids: config
+                    .bindgroup_addids()
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_default(),
+            };
+            match categories.iter_mut().find(|v| v.name == *props.category()) {
+                Some(category) => category.parts.push(part),
+                None => categories.push(DressupCategory {
+                    name: props.category().clone(),
+                    parts: vec![part],
+                    is_mustselect: false,
+                    is_multiple: false,
+                }),
+            }
+        }
+        for option in config.bindoptions().values() {
+            match categories.iter_mut().find(|v| v.name == *option.category()) {
+                Some(category) => {
+                    category.is_mustselect |= option.is_mustselect();
+                    category.is_multiple |= option.is_multiple();
+                }
+                None => errors.push(DressupError::UnmatchedOption {
+                    scope,
+                    id: *option.id(),
+                    category: option.category().clone(),
+                }),
+            }
+        }
+
+        (Dressup { categories }, errors)
+    }
+
+    pub fn categories(&self) -> &Vec<DressupCategory> {
+        &self.categories
+    }
+
+    /// Category named `name`.
+    pub fn category(&self, name: &str) -> Option<&DressupCategory> {
+        self.categories.iter().find(|v| v.name == name)
+    }
+
+    /// Part of `id` with the category it belongs to.
+    pub fn find_part(&self, id: AnimationIdType) -> Option<(&DressupCategory, &DressupPart)> {
+        self.categories
+            .iter()
+            .find_map(|c| c.parts.iter().find(|p| p.id == id).map(|p| (c, p)))
+    }
+
+    /// Part of `id`.
+    pub fn part(&self, id: AnimationIdType) -> Option<&DressupPart> {
+        self.find_part(id).map(|(_, v)| v)
+    }
+}
+
+impl<'a> DressupState<'a> {
+    /// Starts from the parts selected by `bindgroupN.default`.
+    ///
+    /// When a category without `multiple` has more than one default part, the one with
+    /// the smallest id is selected, and a `mustselect` category without any default part
+    /// gets its first part selected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::{parse, DressupState};
+    ///
+    /// let case = r#"charset,UTF-8
+    /// sakura.bindgroup0.name,帽子,麦わら
+    /// sakura.bindgroup1.name,帽子,リボン
+    /// sakura.bindgroup1.addid,10
+    /// sakura.bindgroup0.default,1
+    /// sakura.bindoption0.group,帽子,mustselect
+    /// "#;
+    ///
+    /// let dressup = parse(case).unwrap().dressup(0).0;
+    /// let mut state = DressupState::new(&dressup);
+    /// assert!(!state.toggle(0));
+    /// assert!(state.toggle(1));
+    /// assert_eq!(state.active_animation_ids().into_iter().collect::<Vec<_>>(), vec![1, 10]);
+    /// ```
+    pub fn new(dressup: &'a Dressup) -> DressupState<'a> {
+        let mut active = BTreeSet::new();
+        for category in dressup.categories.iter() {
+            let defaults = category.parts.iter().filter(|v| v.is_default);
+            if category.is_multiple {
+                active.extend(defaults.map(|v| v.id));
+            } else if let Some(part) = defaults.min_by_key(|v| v.id) {
+                active.insert(part.id);
+            }
+            if category.is_mustselect && !category.parts.iter().any(|v| active.contains(&v.id)) {
+                if let Some(part) = category.parts.first() {
+                    active.insert(part.id);
+                }
+            }
+        }
+        DressupState { dressup, active }
+    }
+
+    pub fn dressup(&self) -> &Dressup {
+        self.dressup
+    }
+
+    /// Ids of the selected parts.
+    pub fn active_parts(&self) -> &BTreeSet<AnimationIdType> {
+        &self.active
+    }
+
+    pub fn is_active(&self, id: AnimationIdType) -> bool {
+        self.active.contains(&id)
+    }
+
+    /// Selects the part of `id` if it is not selected, or deselects it otherwise.
+    ///
+    /// Returns whether anything changed. Unknown ids change nothing.
+    pub fn toggle(&mut self, id: AnimationIdType) -> bool {
+        self.set_active(id, !self.is_active(id))
+    }
+
+    /// Selects or deselects the part of `id`, returning whether anything changed.
+    ///
+    /// Deselecting the last selected part of a `mustselect` category changes nothing, and
+    /// selecting a part of a category without `multiple` deselects the other parts of it.
+    pub fn set_active(&mut self, id: AnimationIdType, active: bool) -> bool {
+        let category = match self.dressup.find_part(id) {
+            Some((v, _)) => v,
+            None => return false,
+        };
+        if active == self.is_active(id) {
+            return false;
+        }
+
+        if active {
+            if !category.is_multiple {
+                for part in category.parts.iter() {
+                    self.active.remove(&part.id);
+                }
+            }
+            self.active.insert(id);
+        } else {
+            let others = category
+                .parts
+                .iter()
+                .filter(|v| v.id != id && self.active.contains(&v.id))
+                .count();
+            if category.is_mustselect && others == 0 {
+                return false;
+            }
+            self.active.remove(&id);
+        }
+        true
+    }
+
+    /// Ids of the selected parts together with the ids they add by `bindgroupN.addid`.
+    pub fn active_animation_ids(&self) -> BTreeSet<AnimationIdType> {
+        let mut ids = self.active.clone();
+        for id in self.active.iter() {
+            if let Some(part) = self.dressup.part(*id) {
+                ids.extend(part.addids.iter());
+            }
+        }
+        ids
+    }
+}
+
+impl DressupCategory {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn parts(&self) -> &Vec<DressupPart> {
+        &self.parts
+    }
+    /// Whether at least one part must always be selected.
+    pub fn is_mustselect(&self) -> &bool {
+        &self.is_mustselect
+    }
+    /// Whether more than one part can be selected at once.
+    pub fn is_multiple(&self) -> &bool {
+        &self.is_multiple
+    }
+}
+
+impl DressupPart {
+    pub fn id(&self) -> &AnimationIdType {
+        &self.id
+    }
+    pub fn part_name(&self) -> &String {
+        &self.part_name
+    }
+    pub fn thumbnail_name(&self) -> &Option<String> {
+        &self.thumbnail_name
+    }
+    /// Whether the part is selected by default.
+    pub fn is_default(&self) -> &bool {
+        &self.is_default
+    }
+    /// Animation ids activated together with the part.
+    pub fn addids(&self) -> &Vec<AnimationIdType> {
+        &self.addids
+    }
+}
+
+impl fmt::Display for DressupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DressupError::UndefinedDefault { scope, id } => write!(
+                f,
+                "bindgroup{}.default of scope {} refers to undefined bindgroup{}.name",
+                id, scope, id
+            ),
+            DressupError::UndefinedAddid { scope, id } => write!(
+                f,
+                "bindgroup{}.addid of scope {} refers to undefined bindgroup{}.name",
+                id, scope, id
+            ),
+            DressupError::UnmatchedOption {
+                scope,
+                id,
+                category,
+            } => write!(
+                f,
+                "bindoption{}.group of scope {} is for category {} which no bindgroup has",
+                id, scope, category
+            ),
+        }
+    }
+}
+
+impl Error for DressupError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::parse;
+
+    mod dressup {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+char0.bindgroup5.name,腕,腕輪
+sakura.bindgroup3.name,服,ドレス,dress
+sakura.bindgroup0.name,服,エプロン,apron
+sakura.bindgroup1.name,リボン,白リボン
+sakura.bindgroup0.default,1
+sakura.bindgroup1.default,0
+sakura.bindgroup3.addid,30,31
+sakura.bindoption0.group,服,mustselect
+sakura.bindoption1.group,服,multiple
+sakura.bindoption2.group,靴,multiple
+kero.bindgroup0.name,首,ネクタイ
+"#;
+            let descript = parse(case).unwrap();
+            let result = descript.dressup(0).0;
+            let names: Vec<&str> = result
+                .categories()
+                .iter()
+                .map(|v| v.name().as_str())
+                .collect();
+            assert_eq!(names, vec!["服", "リボン", "腕"]);
+
+            let cloth = result.category("服").unwrap();
+            assert!(cloth.is_mustselect());
+            assert!(cloth.is_multiple());
+            assert_eq!(
+                cloth.parts(),
+                &vec![
+                    DressupPart {
+                        id: 0,
+                        part_name: "エプロン".to_string(),
+                        thumbnail_name: Some("apron".to_string()),
+                        is_default: true,
+                        addids: vec![],
+                    },
+                    DressupPart {
+                        id: 3,
+                        part_name: "ドレス".to_string(),
+                        thumbnail_name: Some("dress".to_string()),
+                        is_default: false,
+                        addids: vec![30, 31],
+                    },
+                ]
+            );
+            let ribbon = result.category("リボン").unwrap();
+            assert!(!ribbon.is_mustselect());
+            assert!(!ribbon.parts()[0].is_default());
+            assert_eq!(
+                result.find_part(5).map(|(c, _)| c.name().as_str()),
+                Some("腕")
+            );
+            assert_eq!(result.part(4), None);
+
+            let result = descript.dressup(1).0;
+            assert_eq!(result.categories().len(), 1);
+            assert_eq!(descript.dressup(2), (Dressup::default(), vec![]));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = r#"charset,UTF-8
+char2.bindgroup0.name,服,エプロン
+char2.bindgroup1.default,1
+char2.bindgroup2.addid,3
+char2.bindoption0.group,帽子,multiple
+"#;
+            let (dressup, result) = parse(case).unwrap().dressup(2);
+            assert_eq!(dressup.categories().len(), 1);
+            assert_eq!(dressup.part(0).unwrap().part_name(), "エプロン");
+            assert_eq!(
+                result,
+                vec![
+                    DressupError::UndefinedDefault { scope: 2, id: 1 },
+                    DressupError::UndefinedAddid { scope: 2, id: 2 },
+                    DressupError::UnmatchedOption {
+                        scope: 2,
+                        id: 0,
+                        category: "帽子".to_string(),
+                    },
+                ]
+            );
+            assert_eq!(
+                result[0].to_string(),
+                "bindgroup1.default of scope 2 refers to undefined bindgroup1.name"
+            );
+        }
+    }
+
+    mod dressup_state {
+        use super::*;
+
+        const CASE: &str = r#"charset,UTF-8
+sakura.bindgroup0.name,帽子,麦わら
+sakura.bindgroup1.name,帽子,リボン
+sakura.bindgroup2.name,服,エプロン
+sakura.bindgroup3.name,服,ドレス
+sakura.bindgroup4.name,アクセサリ,腕輪
+sakura.bindgroup5.name,アクセサリ,指輪
+sakura.bindgroup6.name,靴,ブーツ
+sakura.bindgroup1.default,1
+sakura.bindgroup2.default,1
+sakura.bindgroup3.default,1
+sakura.bindgroup4.default,1
+sakura.bindgroup5.default,1
+sakura.bindgroup3.addid,30,31
+sakura.bindgroup5.addid,50
+sakura.bindoption0.group,服,mustselect
+sakura.bindoption1.group,アクセサリ,multiple
+sakura.bindoption2.group,靴,mustselect
+"#;
+
+        #[test]
+        fn success_when_valid_str() {
+            let dressup = parse(CASE).unwrap().dressup(0).0;
+            let state = DressupState::new(&dressup);
+            assert_eq!(
+                state.active_parts().iter().copied().collect::<Vec<_>>(),
+                vec![1, 2, 4, 5, 6]
+            );
+            assert_eq!(
+                state.active_animation_ids().into_iter().collect::<Vec<_>>(),
+                vec![1, 2, 4, 5, 6, 50]
+            );
+        }
+
+        #[test]
+        fn success_when_toggled() {
+            let dressup = parse(CASE).unwrap().dressup(0).0;
+            let mut state = DressupState::new(&dressup);
+
+            // neither mustselect nor multiple
+            assert!(state.toggle(0));
+            assert!(state.is_active(0));
+            assert!(!state.is_active(1));
+            assert!(state.toggle(0));
+            assert!(!state.is_active(0));
+
+            // mustselect
+            assert!(!state.toggle(2));
+            assert!(state.toggle(3));
+            assert!(!state.is_active(2));
+            assert!(!state.set_active(3, false));
+            assert!(state.is_active(3));
+
+            // multiple
+            assert!(state.toggle(4));
+            assert!(state.toggle(5));
+            assert!(state.toggle(4));
+            assert!(state.is_active(4) && !state.is_active(5));
+
+            assert!(!state.set_active(6, true));
+            assert!(!state.toggle(100));
+
+            assert_eq!(
+                state.active_animation_ids().into_iter().collect::<Vec<_>>(),
+                vec![3, 4, 6, 30, 31]
+            );
+        }
+    }
+}
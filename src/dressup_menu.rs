@@ -0,0 +1,319 @@
+//! Lays out the dressup menu of a character from `menuitemN` and `menuitemexN`.
+
+use crate::ast::{
+    AnimationIdType, BindMenuItem, BindMenuVisibility, CharacterIdType, ShellDescript,
+};
+use crate::dressup::{Dressup, DressupError};
+use crate::resolve::{CharacterConfig, ShellConfig};
+
+/// Dressup menu of a character as the baseware shows it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DressupMenu {
+    is_visible: bool,
+    is_auto: bool,
+    items: Vec<DressupMenuItem>,
+}
+
+/// Item of a [`DressupMenu`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DressupMenuItem {
+    /// Part to toggle.
+    Part {
+        id: AnimationIdType,
+        category: String,
+        part_name: String,
+    },
+    Separator,
+    /// Submenu named by `menuitemexN`, or by the category in automatic layout.
+    Submenu {
+        name: String,
+        items: Vec<DressupMenuItem>,
+    },
+}
+
+impl ShellDescript {
+    /// Lays out the dressup menu of the character at `scope`.
+    ///
+    /// Errors of the dressup, as [`ShellDescript::dressup`] returns, come beside the menu.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::{parse, DressupMenuItem};
+    ///
+    /// let case = r#"charset,UTF-8
+    /// sakura.bindgroup0.name,帽子,麦わら
+    /// sakura.bindgroup1.name,服,エプロン
+    /// sakura.menuitem0,1
+    /// sakura.menuitem1,-
+    /// sakura.menuitemex2,帽子,0
+    /// "#;
+    ///
+    /// let menu = parse(case).unwrap().dressup_menu(0).0;
+    /// assert!(menu.is_visible());
+    /// assert_eq!(menu.items().len(), 3);
+    /// assert_eq!(menu.items()[1], DressupMenuItem::Separator);
+    /// assert!(matches!(&menu.items()[2], DressupMenuItem::Submenu { name, .. } if name == "帽子"));
+    /// ```
+    pub fn dressup_menu(&self, scope: CharacterIdType) -> (DressupMenu, Vec<DressupError>) {
+        self.resolve().dressup_menu(scope)
+    }
+}
+
+impl ShellConfig {
+    /// Lays out the dressup menu of the character at `scope`.
+    pub fn dressup_menu(&self, scope: CharacterIdType) -> (DressupMenu, Vec<DressupError>) {
+        let settings = self.character(scope);
+        let (dressup, errors) = Dressup::from_config(scope, settings.config());
+        (
+            DressupMenu::from_config(settings.config(), &dressup),
+            errors,
+        )
+    }
+}
+
+impl DressupMenu {
+    /// Lays out the menu of `dressup` from the settings of its character.
+    ///
+    /// Items follow the order of `N` of `menuitemN` and `menuitemexN`, where items of
+    /// `menuitemexN` go into the submenu of the given name, placed where the first of them is.
+    /// `menuitemexN` replaces `menuitemN` of the same `N`.
+    /// Items referring to undefined ids are left out as the baseware does.
+    ///
+    /// Without any `menuitemN` and `menuitemexN`, or with `menu,auto`, each category becomes
+    /// a submenu of its parts. With `menu,hidden`, the menu is not shown and has no items.
+    pub fn from_config(config: &CharacterConfig, dressup: &Dressup) -> DressupMenu {
+        match config.menu() {
+            Some(BindMenuVisibility::Hidden) => {
+                return DressupMenu {
+                    is_visible: false,
+                    is_auto: false,
+                    items: Vec::new(),
+                }
+            }
+            Some(BindMenuVisibility::Auto) => return DressupMenu::auto(dressup),
+            None => {}
+        }
+        if config.menuitems().is_empty() && config.menuitemexes().is_empty() {
+            return DressupMenu::auto(dressup);
+        }
+
+        let mut entries: Vec<(u32, Option<&String>, &BindMenuItem)> = config
+            .menuitems()
+            .iter()
+            .filter(|(i, _)| !config.menuitemexes().contains_key(i))
+            .map(|(i, v)| (*i, None, v))
+            .chain(
+                config
+                    .menuitemexes()
+                    .iter()
+                    .map(|(i, (name, v))| (*i, Some(name), v)),
+            )
+            .collect();
+        entries.sort_by_key(|(i, _, _)| *i);
+
+        let mut items: Vec<DressupMenuItem> = Vec::new();
+        for (_, submenu, item) in entries.into_iter() {
+            let item = match item {
+                BindMenuItem::Line => DressupMenuItem::Separator,
+                BindMenuItem::Id(id) => match dressup.find_part(*id) {
+                    Some((category, part)) => DressupMenuItem::Part {
+                        id: *id,
+                        category: category.name().clone(),
+                        part_name: part.part_name().clone(),
+                    },
+                    None => continue,
+                },
+            };
+            let name = match submenu {
+                Some(v) => v,
+                None => {
+                    items.push(item);
+                    continue;
+                }
+            };
+            let found = items.iter_mut().find_map(|v| match v {
+                DressupMenuItem::Submenu { name: n, items } if n == name => Some(items),
+                _ => None,
+            });
+            match found {
+                Some(v) => v.push(item),
+                None => items.push(DressupMenuItem::Submenu {
+                    name: name.clone(),
+                    items: vec![item],
+                }),
+            }
+        }
+
+        DressupMenu {
+            is_visible: true,
+            is_auto: false,
+            items,
+        }
+    }
+
+    fn auto(dressup: &Dressup) -> DressupMenu {
+        let items = dressup
+            .categories()
+            .iter()
+            .map(|category| DressupMenuItem::Submenu {
+                name: category.name().clone(),
+                items: category
+                    .parts()
+                    .iter()
+                    .map(|part| DressupMenuItem::Part {
+                        id: *part.id(),
+                        category: category.name().clone(),
+                        part_name: part.part_name().clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        DressupMenu {
+            is_visible: true,
+            is_auto: true,
+            items,
+        }
+    }
+
+    /// Whether the menu is shown, `false` with `menu,hidden`.
+    pub fn is_visible(&self) -> &bool {
+        &self.is_visible
+    }
+    /// Whether the menu is laid out automatically from the categories.
+    pub fn is_auto(&self) -> &bool {
+        &self.is_auto
+    }
+    pub fn items(&self) -> &Vec<DressupMenuItem> {
+        &self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::parse;
+
+    fn part(id: AnimationIdType, category: &str, part_name: &str) -> DressupMenuItem {
+        DressupMenuItem::Part {
+            id,
+            category: category.to_string(),
+            part_name: part_name.to_string(),
+        }
+    }
+
+    mod dressup_menu {
+        use super::*;
+
+        const BINDGROUPS: &str = r#"charset,UTF-8
+kero.bindgroup0.name,帽子,麦わら
+kero.bindgroup1.name,帽子,リボン
+kero.bindgroup2.name,服,エプロン
+kero.bindgroup3.name,服,ドレス
+"#;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = format!(
+                "{}{}",
+                BINDGROUPS,
+                r#"kero.menuitem3,2
+kero.menuitemex1,帽子,0
+kero.menuitem0,3
+kero.menuitem2,-
+kero.menuitemex4,帽子,-
+kero.menuitemex5,帽子,1
+kero.menuitem6,9
+"#
+            );
+            let result = parse(&case).unwrap().dressup_menu(1).0;
+            assert!(result.is_visible());
+            assert!(!result.is_auto());
+            assert_eq!(
+                result.items(),
+                &vec![
+                    part(3, "服", "ドレス"),
+                    DressupMenuItem::Submenu {
+                        name: "帽子".to_string(),
+                        items: vec![
+                            part(0, "帽子", "麦わら"),
+                            DressupMenuItem::Separator,
+                            part(1, "帽子", "リボン"),
+                        ],
+                    },
+                    DressupMenuItem::Separator,
+                    part(2, "服", "エプロン"),
+                ]
+            );
+        }
+
+        #[test]
+        fn success_when_menuitemex_replaces_menuitem() {
+            use crate::lint::{DuplicateMenuitem, Linter};
+
+            let case = format!(
+                "{}kero.menuitem0,1\nkero.menuitemex0,帽子,0\nkero.menuitem1,2\n",
+                BINDGROUPS
+            );
+            let descript = parse(&case).unwrap();
+            let result = descript.dressup_menu(1).0;
+            assert_eq!(
+                result.items(),
+                &vec![
+                    DressupMenuItem::Submenu {
+                        name: "帽子".to_string(),
+                        items: vec![part(0, "帽子", "麦わら")],
+                    },
+                    part(2, "服", "エプロン"),
+                ]
+            );
+
+            let result = Linter::empty().rule(DuplicateMenuitem).check(&descript);
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].index(), &Some(6));
+        }
+
+        #[test]
+        fn success_when_auto() {
+            let expected = vec![
+                DressupMenuItem::Submenu {
+                    name: "帽子".to_string(),
+                    items: vec![part(0, "帽子", "麦わら"), part(1, "帽子", "リボン")],
+                },
+                DressupMenuItem::Submenu {
+                    name: "服".to_string(),
+                    items: vec![part(2, "服", "エプロン"), part(3, "服", "ドレス")],
+                },
+            ];
+
+            let result = parse(BINDGROUPS).unwrap().dressup_menu(1).0;
+            assert!(result.is_auto());
+            assert_eq!(result.items(), &expected);
+
+            let case = format!("{}kero.menuitem0,1\nkero.menu,auto\n", BINDGROUPS);
+            let result = parse(&case).unwrap().dressup_menu(1).0;
+            assert!(result.is_auto());
+            assert_eq!(result.items(), &expected);
+        }
+
+        #[test]
+        fn success_when_hidden() {
+            let case = format!("{}kero.menuitem0,1\nkero.menu,hidden\n", BINDGROUPS);
+            let result = parse(&case).unwrap().dressup_menu(1).0;
+            assert!(!result.is_visible());
+            assert_eq!(result.items(), &vec![]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = format!("{}kero.bindgroup5.default,1\n", BINDGROUPS);
+            let (menu, errors) = parse(&case).unwrap().dressup_menu(1);
+            assert!(menu.is_visible());
+            assert_eq!(
+                errors,
+                vec![DressupError::UndefinedDefault { scope: 1, id: 5 }]
+            );
+        }
+    }
+}
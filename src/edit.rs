@@ -0,0 +1,360 @@
+//! Edits a [`ShellDescript`] keeping comments, blank lines and order of the other lines.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use crate::ast::{LineContainer, ShellDescript, ShellDescriptLine};
+use crate::parse::keys::key_matches;
+
+/// Group of keys, in the order they usually appear in descript.txt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section {
+    Base,
+    ShellRepresentation,
+    BalloonRepresentation,
+    Menu,
+    Binding,
+    Alpha,
+}
+
+impl ShellDescriptLine {
+    /// Returns the section the key belongs to.
+    pub fn section(&self) -> Section {
+        match self {
+            ShellDescriptLine::Charset(_)
+            | ShellDescriptLine::Name(_)
+            | ShellDescriptLine::Id(_)
+            | ShellDescriptLine::Type
+            | ShellDescriptLine::Craftman(_)
+            | ShellDescriptLine::Craftmanw(_)
+            | ShellDescriptLine::Craftmanurl(_)
+            | ShellDescriptLine::Homeurl(_)
+            | ShellDescriptLine::Readme(_)
+            | ShellDescriptLine::ReadmeCharset(_)
+            | ShellDescriptLine::MenuHidden
+            | ShellDescriptLine::SakuraName(_)
+            | ShellDescriptLine::SakuraName2(_)
+            | ShellDescriptLine::KeroName(_)
+            | ShellDescriptLine::CharName(_, _) => Section::Base,
+            ShellDescriptLine::SerikoZOrder(_)
+            | ShellDescriptLine::SerikoStickyWindow(_)
+            | ShellDescriptLine::SerikoAlignmenttodesktop(_)
+            | ShellDescriptLine::SakuraSerikoAlignmenttodesktop(_)
+            | ShellDescriptLine::KeroSerikoAlignmenttodesktop(_)
+            | ShellDescriptLine::CharSerikoAlignmenttodesktop(_, _)
+            | ShellDescriptLine::SakuraDefaultx(_)
+            | ShellDescriptLine::KeroDefaultx(_)
+            | ShellDescriptLine::CharDefaultx(_, _)
+            | ShellDescriptLine::SakuraDefaulty(_)
+            | ShellDescriptLine::KeroDefaulty(_)
+            | ShellDescriptLine::CharDefaulty(_, _)
+            | ShellDescriptLine::SakuraDefaultleft(_)
+            | ShellDescriptLine::KeroDefaultleft(_)
+            | ShellDescriptLine::CharDefaultleft(_, _)
+            | ShellDescriptLine::SakuraDefaulttop(_)
+            | ShellDescriptLine::KeroDefaulttop(_)
+            | ShellDescriptLine::CharDefaulttop(_, _) => Section::ShellRepresentation,
+            ShellDescriptLine::SakuraBalloonOffsetx(_)
+            | ShellDescriptLine::SakuraBalloonOffsety(_)
+            | ShellDescriptLine::KeroBalloonOffsetx(_)
+            | ShellDescriptLine::KeroBalloonOffsety(_)
+            | ShellDescriptLine::SakuraBalloonAlignment(_)
+            | ShellDescriptLine::KeroBalloonAlignment(_)
+            | ShellDescriptLine::SakuraBalloonDontmove(_)
+            | ShellDescriptLine::KeroBalloonDontmove(_)
+            | ShellDescriptLine::CharBalloonDontmove(_, _) => Section::BalloonRepresentation,
+            ShellDescriptLine::MenuFontName(_)
+            | ShellDescriptLine::MenuFontHeight(_)
+            | ShellDescriptLine::MenuBackgroundBitmapFilename(_)
+            | ShellDescriptLine::MenuForegroundBitmapFilename(_)
+            | ShellDescriptLine::MenuSidebarBitmapFilename(_)
+            | ShellDescriptLine::MenuBackgroundFontColorR(_)
+            | ShellDescriptLine::MenuBackgroundFontColorG(_)
+            | ShellDescriptLine::MenuBackgroundFontColorB(_)
+            | ShellDescriptLine::MenuForegroundFontColorR(_)
+            | ShellDescriptLine::MenuForegroundFontColorG(_)
+            | ShellDescriptLine::MenuForegroundFontColorB(_)
+            | ShellDescriptLine::MenuSeparatorColorR(_)
+            | ShellDescriptLine::MenuSeparatorColorG(_)
+            | ShellDescriptLine::MenuSeparatorColorB(_)
+            | ShellDescriptLine::MenuFrameColorR(_)
+            | ShellDescriptLine::MenuFrameColorG(_)
+            | ShellDescriptLine::MenuFrameColorB(_)
+            | ShellDescriptLine::MenuDisableFontColorR(_)
+            | ShellDescriptLine::MenuDisableFontColorG(_)
+            | ShellDescriptLine::MenuDisableFontColorB(_)
+            | ShellDescriptLine::MenuBackgroundAlignment(_, _, _)
+            | ShellDescriptLine::MenuForegroundAlignment(_, _, _)
+            | ShellDescriptLine::MenuSidebarAlignment(_, _) => Section::Menu,
+            ShellDescriptLine::SakuraBindgroupName(_)
+            | ShellDescriptLine::SakuraBindgroupDefault(_, _)
+            | ShellDescriptLine::SakuraBindgroupAddid(_, _)
+            | ShellDescriptLine::SakuraBindoptionGroup(_)
+            | ShellDescriptLine::SakuraMenuitem(_, _)
+            | ShellDescriptLine::SakuraMenuitemex(_, _, _)
+            | ShellDescriptLine::SakuraMenu(_)
+            | ShellDescriptLine::KeroBindgroupName(_)
+            | ShellDescriptLine::KeroBindgroupDefault(_, _)
+            | ShellDescriptLine::KeroBindgroupAddid(_, _)
+            | ShellDescriptLine::KeroBindoptionGroup(_)
+            | ShellDescriptLine::KeroMenuitem(_, _)
+            | ShellDescriptLine::KeroMenuitemex(_, _, _)
+            | ShellDescriptLine::KeroMenu(_)
+            | ShellDescriptLine::CharBindgroupName(_, _)
+            | ShellDescriptLine::CharBindgroupDefault(_, _, _)
+            | ShellDescriptLine::CharBindgroupAddid(_, _, _)
+            | ShellDescriptLine::CharBindoptionGroup(_, _)
+            | ShellDescriptLine::CharMenuitem(_, _, _)
+            | ShellDescriptLine::CharMenuitemex(_, _, _, _)
+            | ShellDescriptLine::CharMenu(_, _) => Section::Binding,
+            ShellDescriptLine::SerikoPaintTransparentRegionBlack(_)
+            | ShellDescriptLine::SerikoUseSelfAlpha(_) => Section::Alpha,
+        }
+    }
+}
+
+impl ShellDescript {
+    /// Sets `line`, returning the line it replaced.
+    ///
+    /// When lines with the same key exist, the last of them, which is the effective one,
+    /// is updated in place. Otherwise `line` is inserted after the last line of the same
+    /// [`Section`], or appended after a blank line when the section has no lines yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::{parse_lossless, ShellDescriptLine};
+    ///
+    /// let case = "charset,UTF-8\r\n//position\r\nsakura.defaultx,10\r\n\r\nsakura.menuitem0,-\r\n";
+    ///
+    /// let mut descript = parse_lossless(case).unwrap();
+    /// descript.set(ShellDescriptLine::SakuraDefaultx(20));
+    /// descript.set(ShellDescriptLine::KeroDefaultx(-40));
+    /// assert_eq!(
+    ///     descript.to_string(),
+    ///     "charset,UTF-8\r\n//position\r\nsakura.defaultx,20\r\nkero.defaultx,-40\r\n\r\nsakura.menuitem0,-\r\n"
+    /// );
+    /// ```
+    pub fn set(&mut self, line: ShellDescriptLine) -> Option<ShellDescriptLine> {
+        let key = line.key();
+        let existing = self.lines().iter().rposition(|v| match v {
+            LineContainer::Body(v) => v.key() == key,
+            _ => false,
+        });
+        if let Some(index) = existing {
+            if self.lines()[index] == LineContainer::Body(line.clone()) {
+                return Some(line);
+            }
+            return match self.replace(index, LineContainer::Body(line)) {
+                LineContainer::Body(v) => Some(v),
+                _ => None,
+            };
+        }
+
+        let section = line.section();
+        let last_in_section = self.lines().iter().rposition(|v| match v {
+            LineContainer::Body(v) => v.section() == section,
+            _ => false,
+        });
+        match last_in_section {
+            Some(index) => self.insert(index + 1, LineContainer::Body(line)),
+            None => {
+                if self
+                    .lines()
+                    .last()
+                    .is_some_and(|v| *v != LineContainer::EmptyLine)
+                {
+                    self.push(LineContainer::EmptyLine);
+                }
+                self.push(LineContainer::Body(line));
+            }
+        }
+        None
+    }
+
+    /// Removes all the lines whose key matches `key`, returning them.
+    ///
+    /// `key` may have wildcards as in [`ShellDescript::get`], e.g. `menu.**.color.*`
+    /// removes every menu colour. Lines of a matching key with an invalid value are
+    /// removed too.
+    pub fn remove_key(&mut self, key: &str) -> Vec<LineContainer> {
+        let matches = |v: &LineContainer| match v {
+            LineContainer::Body(v) => key_matches(key, &v.key()),
+            LineContainer::Invalid { key: k, .. } => key_matches(key, k),
+            _ => false,
+        };
+        let removed = self
+            .lines()
+            .iter()
+            .filter(|v| matches(v))
+            .cloned()
+            .collect();
+        self.retain(|v| !matches(v));
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use shell_parser_common_rs::charset::Charset;
+
+    use crate::ast::{BindGroupNameProps, BindMenuItem, LineSource, Newline};
+    use crate::parse::parse_lossless;
+
+    const CASE: &str = "charset,UTF-8\r\nname,master\r\n\r\n// position\r\nsakura.defaultx,10\r\nsakura.defaultx,20\r\n\r\nmenu.frame.color.r,10\r\nmenu.frame.color.g,20\r\nsakura.menuitem0,-\r\n";
+
+    mod section {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            assert_eq!(ShellDescriptLine::Type.section(), Section::Base);
+            assert_eq!(
+                ShellDescriptLine::CharDefaulttop(2, 0).section(),
+                Section::ShellRepresentation
+            );
+            assert_eq!(
+                ShellDescriptLine::MenuFrameColorB(0).section(),
+                Section::Menu
+            );
+            assert_eq!(
+                ShellDescriptLine::SerikoUseSelfAlpha(1).section(),
+                Section::Alpha
+            );
+        }
+    }
+
+    mod set {
+        use super::*;
+
+        #[test]
+        fn success_when_key_exists() {
+            let mut descript = parse_lossless(CASE).unwrap();
+            let result = descript.set(ShellDescriptLine::SakuraDefaultx(-40));
+            assert_eq!(result, Some(ShellDescriptLine::SakuraDefaultx(20)));
+            assert_eq!(
+                descript.to_string(),
+                CASE.replace("sakura.defaultx,20", "sakura.defaultx,-40")
+            );
+            assert_eq!(descript.sources()[5], None);
+            assert!(descript.sources()[4].is_some());
+        }
+
+        #[test]
+        fn success_when_section_exists() {
+            let mut descript = parse_lossless(CASE).unwrap();
+            let result = descript.set(ShellDescriptLine::KeroDefaultx(-40));
+            assert_eq!(result, None);
+            assert_eq!(
+                descript.to_string(),
+                CASE.replace(
+                    "sakura.defaultx,20\r\n",
+                    "sakura.defaultx,20\r\nkero.defaultx,-40\r\n"
+                )
+            );
+        }
+
+        #[test]
+        fn success_when_section_not_exists() {
+            let mut descript = parse_lossless(CASE).unwrap();
+            descript.set(ShellDescriptLine::SerikoUseSelfAlpha(1));
+            assert_eq!(
+                descript.to_string(),
+                format!("{}\r\nseriko.use_self_alpha,1\r\n", CASE)
+            );
+        }
+
+        #[test]
+        fn success_when_same_line() {
+            let case = "charset,UTF-8\nsakura.bindgroup0.name,帽子,麦わら\n";
+            let mut descript = parse_lossless(case).unwrap();
+            let line = ShellDescriptLine::SakuraBindgroupName(BindGroupNameProps::new(
+                0,
+                "帽子".to_string(),
+                "麦わら".to_string(),
+                None,
+            ));
+            assert_eq!(descript.set(line.clone()), Some(line));
+            assert_eq!(
+                descript.sources()[1],
+                Some(LineSource::new(
+                    "sakura.bindgroup0.name,帽子,麦わら".to_string(),
+                    Newline::Lf
+                ))
+            );
+        }
+    }
+
+    mod remove_key {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let mut descript = parse_lossless(CASE).unwrap();
+            let result = descript.remove_key("sakura.defaultx");
+            assert_eq!(result.len(), 2);
+            let result = descript.remove_key("sakura.menuitem*");
+            assert_eq!(
+                result,
+                vec![LineContainer::Body(ShellDescriptLine::SakuraMenuitem(
+                    0,
+                    BindMenuItem::Line
+                ))]
+            );
+            assert_eq!(
+                descript.to_string(),
+                "charset,UTF-8\r\nname,master\r\n\r\n// position\r\n\r\nmenu.frame.color.r,10\r\nmenu.frame.color.g,20\r\n"
+            );
+            assert_eq!(descript.spans().len(), descript.lines().len());
+        }
+
+        #[test]
+        fn success_when_wildcard_part() {
+            let case = "charset,UTF-8\r\nmenu.font.name,MS Gothic\r\nmenu.frame.color.r,10\r\nmenu.background.font.color.g,20\r\nmenu.separator.color.b,300\r\nmenu.frame.color.r,x\r\n";
+            let mut descript = parse_lossless(case).unwrap();
+            let result = descript.remove_key("menu.**.color.*");
+            assert_eq!(result.len(), 4);
+            assert!(
+                matches!(&result[2], LineContainer::Invalid { key, .. } if key == "menu.separator.color.b")
+            );
+            assert_eq!(
+                descript.to_string(),
+                "charset,UTF-8\r\nmenu.font.name,MS Gothic\r\n"
+            );
+        }
+
+        #[test]
+        fn success_when_retained_by_predicate() {
+            let mut descript = parse_lossless(CASE).unwrap();
+            descript.retain(|v| match v {
+                LineContainer::Body(v) => !v.key().starts_with("menu.frame.color."),
+                _ => true,
+            });
+            assert_eq!(descript.get("menu.frame.color.r"), None);
+            assert_eq!(descript.lines().len(), 8);
+        }
+    }
+
+    mod replace {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let mut descript = parse_lossless(CASE).unwrap();
+            let old = descript.replace(
+                3,
+                LineContainer::CommentLine("// default position".to_string()),
+            );
+            assert_eq!(old, LineContainer::CommentLine("// position".to_string()));
+            descript.insert(0, LineContainer::CommentLine("// head".to_string()));
+            assert_eq!(
+                descript.remove(1),
+                LineContainer::Body(ShellDescriptLine::Charset(Charset::UTF8))
+            );
+            assert!(descript
+                .to_string()
+                .starts_with("// head\r\nname,master\r\n\r\n// default position\r\n"));
+        }
+    }
+}
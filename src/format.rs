@@ -0,0 +1,296 @@
+//! Formats a [`ShellDescript`] into the canonical layout.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use crate::ast::{LineContainer, LineSource, Newline, ShellDescript, ShellDescriptLine};
+use crate::edit::Section;
+use crate::parse::keys::{key_sample, ANY};
+
+/// Order of a line: section, block within the section, then keys within the block.
+type Order = (usize, (u32, u32), (u32, u32));
+
+impl ShellDescript {
+    /// Returns the lines in the canonical layout.
+    ///
+    /// - Lines are grouped into sections in the order of [`Section`], with `charset` first.
+    ///   Lines of a known key with an invalid value go where a valid one goes.
+    ///   Lines of an unknown key come last.
+    /// - In the binding section, lines are grouped by character, and `bindgroupN`,
+    ///   `bindoptionN` and `menuitemN` are sorted by `N`.
+    /// - Sections and groups are separated by exactly one blank line.
+    /// - Comments move together with the line following them.
+    /// - Every line ends with the first line terminator of the input.
+    ///
+    /// Other lines keep their original text and relative order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::parse_lossless;
+    ///
+    /// let case = "charset,UTF-8\n\n\nmenu.font.name,MS Gothic\n//hat\nsakura.bindgroup1.name,hat,straw\nsakura.bindgroup0.name,cloth,apron\ntype,shell";
+    ///
+    /// let descript = parse_lossless(case).unwrap();
+    /// assert!(!descript.is_formatted());
+    /// assert_eq!(
+    ///     descript.format().to_string(),
+    ///     "charset,UTF-8\ntype,shell\n\nmenu.font.name,MS Gothic\n\nsakura.bindgroup0.name,cloth,apron\n//hat\nsakura.bindgroup1.name,hat,straw\n"
+    /// );
+    /// assert!(descript.format().is_formatted());
+    /// ```
+    pub fn format(&self) -> ShellDescript {
+        let newline = self
+            .sources()
+            .iter()
+            .flatten()
+            .map(|v| v.newline().clone())
+            .find(|v| *v != Newline::Eof)
+            .unwrap_or(Newline::CrLf);
+
+        // Each unit is a line with the comments before it.
+        let mut units: Vec<(Option<Order>, Vec<usize>)> = Vec::new();
+        let mut pending = Vec::new();
+        for (index, line) in self.lines().iter().enumerate() {
+            match line {
+                LineContainer::EmptyLine => {}
+                LineContainer::CommentLine(_) => pending.push(index),
+                _ => {
+                    pending.push(index);
+                    units.push((Some(order(line)), std::mem::take(&mut pending)));
+                }
+            }
+        }
+        if !pending.is_empty() {
+            units.push((None, pending));
+        }
+        // Stable, so lines of the same order keep their relative order.
+        units.sort_by_key(|(order, _)| order.map_or((1, None), |v| (0, Some(v))));
+
+        // `None` for blank lines separating blocks.
+        let mut indexes: Vec<Option<usize>> = Vec::new();
+        let mut block = None;
+        for (order, unit) in units.into_iter() {
+            let current = order.map(|(section, block, _)| (section, block));
+            if !indexes.is_empty() && block != current {
+                indexes.push(None);
+            }
+            block = current;
+            indexes.extend(unit.into_iter().map(Some));
+        }
+
+        let lines = indexes
+            .into_iter()
+            .map(|index| match index {
+                Some(index) => {
+                    let line = self.lines()[index].clone();
+                    let text = match &self.sources()[index] {
+                        Some(v) => v.text().clone(),
+                        None => line.to_string(),
+                    };
+                    (line, LineSource::new(text, newline.clone()))
+                }
+                None => (
+                    LineContainer::EmptyLine,
+                    LineSource::new(String::new(), newline.clone()),
+                ),
+            })
+            .collect();
+        ShellDescript::with_sources(lines)
+    }
+
+    /// Checks whether the lines are already in the canonical layout of [`ShellDescript::format`].
+    pub fn is_formatted(&self) -> bool {
+        self.format().to_string() == self.to_string()
+    }
+}
+
+/// Returns the lines of `descript` in the canonical layout.
+///
+/// Same as [`ShellDescript::format`].
+pub fn format(descript: &ShellDescript) -> ShellDescript {
+    descript.format()
+}
+
+/// Checks whether the lines of `descript` are already in the canonical layout.
+///
+/// Same as [`ShellDescript::is_formatted`].
+pub fn is_formatted(descript: &ShellDescript) -> bool {
+    descript.is_formatted()
+}
+
+fn order(line: &LineContainer) -> Order {
+    match line {
+        LineContainer::Body(v) => body_order(v),
+        LineContainer::Invalid { key, .. } => match key_sample(key) {
+            Some((sample, numbers)) => sample_order(&sample, &numbers),
+            None => (Section::Alpha as usize + 1, (0, 0), (0, 0)),
+        },
+        _ => (Section::Alpha as usize + 1, (0, 0), (0, 0)),
+    }
+}
+
+/// Order of the line for a key given the invalid value, placing it where a valid one goes.
+fn sample_order(sample: &ShellDescriptLine, numbers: &[u32]) -> Order {
+    let (section, (group, scope), (kind, number)) = body_order(sample);
+    let scope = match scope {
+        ANY => numbers.first().copied().unwrap_or_default(),
+        v => v,
+    };
+    let number = match number {
+        ANY => numbers.last().copied().unwrap_or_default(),
+        v => v,
+    };
+    (section, (group, scope), (kind, number))
+}
+
+fn body_order(body: &ShellDescriptLine) -> Order {
+    let section = body.section();
+    match section {
+        Section::Base => (
+            section as usize,
+            (0, 0),
+            (!matches!(body, ShellDescriptLine::Charset(_)) as u32, 0),
+        ),
+        Section::Binding => {
+            let (group, scope, kind, number) = binding_order(body);
+            (section as usize, (group, scope), (kind, number))
+        }
+        _ => (section as usize, (0, 0), (0, 0)),
+    }
+}
+
+/// Group, scope, kind and number of a line in the binding section.
+fn binding_order(line: &ShellDescriptLine) -> (u32, u32, u32, u32) {
+    match line {
+        ShellDescriptLine::SakuraBindgroupName(v) => (0, 0, 0, *v.id()),
+        ShellDescriptLine::KeroBindgroupName(v) => (0, 1, 0, *v.id()),
+        ShellDescriptLine::CharBindgroupName(c, v) => (0, *c, 0, *v.id()),
+        ShellDescriptLine::SakuraBindgroupDefault(id, _) => (0, 0, 1, *id),
+        ShellDescriptLine::KeroBindgroupDefault(id, _) => (0, 1, 1, *id),
+        ShellDescriptLine::CharBindgroupDefault(c, id, _) => (0, *c, 1, *id),
+        ShellDescriptLine::SakuraBindgroupAddid(id, _) => (0, 0, 2, *id),
+        ShellDescriptLine::KeroBindgroupAddid(id, _) => (0, 1, 2, *id),
+        ShellDescriptLine::CharBindgroupAddid(c, id, _) => (0, *c, 2, *id),
+        ShellDescriptLine::SakuraBindoptionGroup(v) => (1, 0, 0, *v.id()),
+        ShellDescriptLine::KeroBindoptionGroup(v) => (1, 1, 0, *v.id()),
+        ShellDescriptLine::CharBindoptionGroup(c, v) => (1, *c, 0, *v.id()),
+        ShellDescriptLine::SakuraMenuitem(i, _) | ShellDescriptLine::SakuraMenuitemex(i, _, _) => {
+            (2, 0, 0, *i)
+        }
+        ShellDescriptLine::KeroMenuitem(i, _) | ShellDescriptLine::KeroMenuitemex(i, _, _) => {
+            (2, 1, 0, *i)
+        }
+        ShellDescriptLine::CharMenuitem(c, i, _)
+        | ShellDescriptLine::CharMenuitemex(c, i, _, _) => (2, *c, 0, *i),
+        ShellDescriptLine::SakuraMenu(_) => (2, 0, 1, 0),
+        ShellDescriptLine::KeroMenu(_) => (2, 1, 1, 0),
+        ShellDescriptLine::CharMenu(c, _) => (2, *c, 1, 0),
+        _ => (0, 0, 0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::{parse, parse_lossless};
+
+    mod format {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"// my shell
+name,master
+charset,UTF-8
+kero.menuitem1,1
+sakura.menuitem10,-
+sakura.menuitem2,3
+kero.bindgroup1.name,腕,腕輪
+kero.bindgroup0.name,首,ネクタイ
+sakura.bindgroup10.name,帽子,リボン
+sakura.bindgroup2.default,1
+// straw hat
+sakura.bindgroup2.name,帽子,麦わら
+
+
+
+seriko.use_self_alpha,1
+sakura.balloon.offsetx,0
+somethingwrong,1
+char2.bindgroup1.default,yes
+menu.font.name,MS Gothic
+sakura.defaulty,top
+sakura.defaultx,10
+// end
+"#;
+            let expected = r#"charset,UTF-8
+// my shell
+name,master
+
+sakura.defaulty,top
+sakura.defaultx,10
+
+sakura.balloon.offsetx,0
+
+menu.font.name,MS Gothic
+
+// straw hat
+sakura.bindgroup2.name,帽子,麦わら
+sakura.bindgroup10.name,帽子,リボン
+sakura.bindgroup2.default,1
+
+kero.bindgroup0.name,首,ネクタイ
+kero.bindgroup1.name,腕,腕輪
+
+char2.bindgroup1.default,yes
+
+sakura.menuitem2,3
+sakura.menuitem10,-
+
+kero.menuitem1,1
+
+seriko.use_self_alpha,1
+
+somethingwrong,1
+
+// end
+"#;
+            let result = parse_lossless(case).unwrap().format();
+            assert_eq!(result.to_string(), expected);
+            assert_eq!(parse(expected).unwrap(), result);
+        }
+
+        #[test]
+        fn success_when_crlf() {
+            let case = "type,shell\r\ncharset,UTF-8";
+            let result = parse_lossless(case).unwrap().format();
+            assert_eq!(result.to_string(), "charset,UTF-8\r\ntype,shell\r\n");
+        }
+
+        #[test]
+        fn success_when_empty() {
+            let result = ShellDescript::new(vec![]).format();
+            assert_eq!(result.to_string(), "");
+        }
+    }
+
+    mod is_formatted {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\nname,master\r\n\r\nsakura.defaultx,10\r\n";
+            assert!(parse_lossless(case).unwrap().is_formatted());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,UTF-8\r\nname,master\r\nsakura.defaultx,10\r\n";
+            assert!(!parse_lossless(case).unwrap().is_formatted());
+
+            let case = "charset,UTF-8\r\n\r\n\r\nname,master\r\n";
+            assert!(!parse_lossless(case).unwrap().is_formatted());
+        }
+    }
+}
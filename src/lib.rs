@@ -41,9 +41,57 @@
 //! };
 //! assert!(!shell_descript.lines().is_empty());
 //! ```
+//!
+//! Or load descript.txt of a shell directory at once:
+//!
+//! ```
+//! use std::path::PathBuf;
+//! use shell_parser_descript_rs::load_shell_dir;
+//!
+//! let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/descript");
+//! let shell_descript = match load_shell_dir(dir) {
+//!     Ok(v) => v,
+//!     Err(e) => {
+//!         eprintln!("{}", e);
+//!         return;
+//!     }
+//! };
+//! assert!(!shell_descript.lines().is_empty());
+//! ```
 
+pub mod assets;
 pub mod ast;
+pub mod bind_animation;
+pub mod builder;
+pub mod diagnostic;
+pub mod dressup;
+pub mod dressup_menu;
+pub mod edit;
+pub mod format;
+pub mod lint;
+pub mod load;
 pub mod parse;
+pub mod query;
+pub mod resolve;
+pub mod thumbnail;
+pub mod write;
+
+#[cfg(test)]
+mod test_support;
 
+pub use assets::*;
 pub use ast::*;
+pub use bind_animation::*;
+pub use builder::*;
+pub use diagnostic::*;
+pub use dressup::*;
+pub use dressup_menu::*;
+pub use edit::*;
+pub use format::*;
+pub use lint::*;
+pub use load::*;
 pub use parse::*;
+pub use query::*;
+pub use resolve::*;
+pub use thumbnail::*;
+pub use write::*;
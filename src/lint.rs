@@ -0,0 +1,531 @@
+//! Checks a [`ShellDescript`] for lines which parse but are likely mistakes.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::ast::{LineContainer, ShellDescript, ShellDescriptLine};
+use crate::diagnostic::{Diagnostic, Severity};
+
+pub use self::dressup::{DuplicateMenuitem, MenuitemGap, UndefinedBindgroup, UnmatchedBindoption};
+
+mod dressup;
+
+/// Rule checked by [`Linter`].
+pub trait LintRule {
+    /// Code of the diagnostics the rule reports, used to enable or disable it.
+    fn code(&self) -> &'static str;
+
+    /// Checks `descript`, pushing found problems into `diagnostics`.
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>);
+}
+
+/// Runs [`LintRule`]s over a [`ShellDescript`].
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::{parse, Linter};
+///
+/// let case = "type,shell\r\ncharset,UTF-8\r\nname,master\r\nname,master2\r\n";
+///
+/// let descript = parse(case).unwrap();
+/// let result = Linter::new().check(&descript);
+/// assert_eq!(result.len(), 2);
+/// assert_eq!(result[0].code(), "duplicate-key");
+/// assert_eq!(result[1].code(), "charset-not-first");
+///
+/// let result = Linter::new().disable("duplicate-key").check(&descript);
+/// assert_eq!(result.len(), 1);
+/// ```
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    disabled: BTreeSet<String>,
+}
+
+impl Linter {
+    /// Creates a linter with all the built-in rules enabled.
+    pub fn new() -> Linter {
+        Linter::empty()
+            .rule(DuplicateKey)
+            .rule(InvalidValue)
+            .rule(UnknownKey)
+            .rule(MissingCharset)
+            .rule(MissingTypeShell)
+            .rule(CharsetNotFirst)
+            .rule(ReadmeCharsetWithoutReadme)
+            .rule(Name2WithoutName)
+            .rule(NonSlashComment)
+            .rule(UndefinedBindgroup)
+            .rule(UnmatchedBindoption)
+            .rule(DuplicateMenuitem)
+            .rule(MenuitemGap)
+    }
+
+    /// Creates a linter with the rules checking ids and names used by dressup lines.
+    pub fn dressup() -> Linter {
+        Linter::empty()
+            .rule(UndefinedBindgroup)
+            .rule(UnmatchedBindoption)
+            .rule(DuplicateMenuitem)
+            .rule(MenuitemGap)
+    }
+
+    /// Creates a linter without rules.
+    pub fn empty() -> Linter {
+        Linter {
+            rules: Vec::new(),
+            disabled: BTreeSet::new(),
+        }
+    }
+
+    /// Adds `rule`.
+    pub fn rule<R: LintRule + 'static>(mut self, rule: R) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Disables the rule of `code`.
+    pub fn disable(mut self, code: &str) -> Self {
+        self.disabled.insert(code.to_string());
+        self
+    }
+
+    /// Enables the rule of `code` disabled before.
+    pub fn enable(mut self, code: &str) -> Self {
+        self.disabled.remove(code);
+        self
+    }
+
+    pub fn is_enabled(&self, code: &str) -> bool {
+        !self.disabled.contains(code)
+    }
+
+    /// Codes of the rules, whether enabled or not.
+    pub fn codes(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|v| v.code()).collect()
+    }
+
+    /// Runs the enabled rules over `descript`.
+    pub fn check(&self, descript: &ShellDescript) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for rule in self.rules.iter().filter(|v| self.is_enabled(v.code())) {
+            rule.check(descript, &mut diagnostics);
+        }
+        diagnostics
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter::new()
+    }
+}
+
+impl ShellDescript {
+    /// Runs all the built-in lint rules.
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        Linter::new().check(self)
+    }
+}
+
+/// Builds a diagnostic pointing at the line at `index`.
+fn at_line(
+    descript: &ShellDescript,
+    index: usize,
+    severity: Severity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic::new(
+        severity,
+        code,
+        message,
+        Some(index),
+        descript.span(index).map(|v| *v.line()),
+    )
+}
+
+/// 1-based line number of the line at `index`, as people reading the file count lines.
+fn line_number(descript: &ShellDescript, index: usize) -> usize {
+    descript
+        .span(index)
+        .map_or(index + 1, |v| *v.line().start().line())
+}
+
+fn bodies(descript: &ShellDescript) -> impl Iterator<Item = (usize, &ShellDescriptLine)> {
+    descript
+        .lines()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| match v {
+            LineContainer::Body(v) => Some((i, v)),
+            _ => None,
+        })
+}
+
+/// Same key given more than once with different values. Only the last one takes effect.
+///
+/// Each repeat is reported with the value given just before it.
+pub struct DuplicateKey;
+
+impl LintRule for DuplicateKey {
+    fn code(&self) -> &'static str {
+        "duplicate-key"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        let mut seen: HashMap<String, (usize, String)> = HashMap::new();
+        for (index, line) in bodies(descript) {
+            let value = line.value();
+            if let Some((previous, previous_value)) = seen.get(&line.key()) {
+                if *previous_value != value {
+                    diagnostics.push(at_line(
+                        descript,
+                        index,
+                        Severity::Warning,
+                        self.code(),
+                        format!(
+                            "{} is already given as `{}` on line {}, and is overridden",
+                            line.key(),
+                            previous_value,
+                            line_number(descript, *previous)
+                        ),
+                    ));
+                }
+            }
+            seen.insert(line.key(), (index, value));
+        }
+    }
+}
+
+/// Known key given a value which cannot be read.
+pub struct InvalidValue;
+
+impl LintRule for InvalidValue {
+    fn code(&self) -> &'static str {
+        "invalid-value"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        for (index, line) in descript.lines().iter().enumerate() {
+            if let LineContainer::Invalid { key, reason, .. } = line {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    self.code(),
+                    format!("{} for {}", reason, key),
+                    Some(index),
+                    descript.span(index).map(|v| v.value().unwrap_or(*v.line())),
+                ));
+            }
+        }
+    }
+}
+
+/// Key which is not known, e.g. a misspelled one.
+pub struct UnknownKey;
+
+impl LintRule for UnknownKey {
+    fn code(&self) -> &'static str {
+        "unknown-key"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        for (index, line) in descript.lines().iter().enumerate() {
+            if let LineContainer::Unknown { key, .. } = line {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    self.code(),
+                    format!("unknown key {}", key),
+                    Some(index),
+                    descript.span(index).map(|v| v.key().unwrap_or(*v.line())),
+                ));
+            }
+        }
+    }
+}
+
+/// `charset` is not given.
+pub struct MissingCharset;
+
+impl LintRule for MissingCharset {
+    fn code(&self) -> &'static str {
+        "missing-charset"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        if !bodies(descript).any(|(_, v)| matches!(v, ShellDescriptLine::Charset(_))) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                self.code(),
+                "charset is not given".to_string(),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+/// `type,shell` is not given.
+pub struct MissingTypeShell;
+
+impl LintRule for MissingTypeShell {
+    fn code(&self) -> &'static str {
+        "missing-type-shell"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        if !bodies(descript).any(|(_, v)| *v == ShellDescriptLine::Type) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                self.code(),
+                "type,shell is not given".to_string(),
+                None,
+                None,
+            ));
+        }
+    }
+}
+
+/// `charset` is given, but not on the first line.
+pub struct CharsetNotFirst;
+
+impl LintRule for CharsetNotFirst {
+    fn code(&self) -> &'static str {
+        "charset-not-first"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        let found = bodies(descript).find(|(_, v)| matches!(v, ShellDescriptLine::Charset(_)));
+        if let Some((index, _)) = found.filter(|(i, _)| *i != 0) {
+            diagnostics.push(at_line(
+                descript,
+                index,
+                Severity::Warning,
+                self.code(),
+                "charset should be on the first line".to_string(),
+            ));
+        }
+    }
+}
+
+/// `readme.charset` is given without `readme`.
+pub struct ReadmeCharsetWithoutReadme;
+
+impl LintRule for ReadmeCharsetWithoutReadme {
+    fn code(&self) -> &'static str {
+        "readme-charset-without-readme"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        if bodies(descript).any(|(_, v)| matches!(v, ShellDescriptLine::Readme(_))) {
+            return;
+        }
+        for (index, _) in
+            bodies(descript).filter(|(_, v)| matches!(v, ShellDescriptLine::ReadmeCharset(_)))
+        {
+            diagnostics.push(at_line(
+                descript,
+                index,
+                Severity::Warning,
+                self.code(),
+                "readme.charset is given without readme".to_string(),
+            ));
+        }
+    }
+}
+
+/// `sakura.name2` is given without `sakura.name`.
+pub struct Name2WithoutName;
+
+impl LintRule for Name2WithoutName {
+    fn code(&self) -> &'static str {
+        "name2-without-name"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        if bodies(descript).any(|(_, v)| matches!(v, ShellDescriptLine::SakuraName(_))) {
+            return;
+        }
+        for (index, _) in
+            bodies(descript).filter(|(_, v)| matches!(v, ShellDescriptLine::SakuraName2(_)))
+        {
+            diagnostics.push(at_line(
+                descript,
+                index,
+                Severity::Warning,
+                self.code(),
+                "sakura.name2 is given without sakura.name".to_string(),
+            ));
+        }
+    }
+}
+
+/// Line read as a comment though it does not start with `//`.
+pub struct NonSlashComment;
+
+impl LintRule for NonSlashComment {
+    fn code(&self) -> &'static str {
+        "non-slash-comment"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        for (index, line) in descript.lines().iter().enumerate() {
+            if let LineContainer::CommentLine(text) = line {
+                if !text.starts_with("//") {
+                    diagnostics.push(at_line(
+                        descript,
+                        index,
+                        Severity::Warning,
+                        self.code(),
+                        format!("`{}` is ignored; comments should start with //", text),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ast::Position;
+    use crate::parse::parse;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|v| v.code().as_str()).collect()
+    }
+
+    mod check {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+type,shell
+name,master
+// comment
+sakura.name,さくら
+sakura.name2,さくら２
+readme,readme.txt
+readme.charset,UTF-8
+sakura.defaultx,10
+sakura.defaultx,10
+"#;
+            let result = Linter::new().check(&parse(case).unwrap());
+            assert_eq!(result, vec![]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = r#"name,master
+charset,UTF-8
+sakura.name2,さくら２
+readme.charset,UTF-8
+sakura.defaultx,10
+sakura.defaultx,20
+this is not comment
+sakura.defaultx,10
+menu.font.height,big
+sakura.defautly,10
+"#;
+            let result = Linter::new().check(&parse(case).unwrap());
+            assert_eq!(
+                codes(&result),
+                vec![
+                    "duplicate-key",
+                    "duplicate-key",
+                    "invalid-value",
+                    "unknown-key",
+                    "missing-type-shell",
+                    "charset-not-first",
+                    "readme-charset-without-readme",
+                    "name2-without-name",
+                    "non-slash-comment",
+                ]
+            );
+            assert_eq!(result[0].index(), &Some(5));
+            assert_eq!(
+                result[0].span().map(|v| *v.start()),
+                Some(Position::new(92, 6, 1))
+            );
+            assert_eq!(
+                result[0].message(),
+                "sakura.defaultx is already given as `10` on line 5, and is overridden"
+            );
+            assert_eq!(result[1].index(), &Some(7));
+            assert_eq!(
+                result[1].message(),
+                "sakura.defaultx is already given as `20` on line 6, and is overridden"
+            );
+            assert_eq!(result[2].index(), &Some(8));
+            assert_eq!(result[2].severity(), &Severity::Error);
+            assert_eq!(
+                result[2].message(),
+                "expected non-negative integer for menu.font.height"
+            );
+            assert_eq!(result[3].index(), &Some(9));
+            assert_eq!(result[3].severity(), &Severity::Warning);
+            assert_eq!(result[4].severity(), &Severity::Warning);
+            assert_eq!(result[4].span(), &None);
+        }
+
+        #[test]
+        fn failed_when_missing_charset() {
+            let result = Linter::new().check(&ShellDescript::new(vec![]));
+            assert_eq!(
+                codes(&result),
+                vec!["missing-charset", "missing-type-shell"]
+            );
+            assert_eq!(result[0].severity(), &Severity::Error);
+        }
+
+        #[test]
+        fn success_when_disabled() {
+            let linter = Linter::new()
+                .disable("missing-charset")
+                .disable("missing-type-shell");
+            assert!(!linter.is_enabled("missing-charset"));
+            assert_eq!(linter.check(&ShellDescript::new(vec![])), vec![]);
+
+            let linter = linter.enable("missing-charset");
+            assert_eq!(
+                codes(&linter.check(&ShellDescript::new(vec![]))),
+                vec!["missing-charset"]
+            );
+        }
+
+        #[test]
+        fn success_when_custom_rule() {
+            struct NoName;
+
+            impl LintRule for NoName {
+                fn code(&self) -> &'static str {
+                    "no-name"
+                }
+
+                fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+                    if descript.get("name").is_none() {
+                        diagnostics.push(Diagnostic::new(
+                            Severity::Info,
+                            self.code(),
+                            "name is not given".to_string(),
+                            None,
+                            None,
+                        ));
+                    }
+                }
+            }
+
+            let linter = Linter::empty().rule(NoName);
+            assert_eq!(linter.codes(), vec!["no-name"]);
+            assert_eq!(
+                codes(&linter.check(&ShellDescript::new(vec![]))),
+                vec!["no-name"]
+            );
+        }
+    }
+}
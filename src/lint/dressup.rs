@@ -0,0 +1,343 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::{
+    AnimationIdType, BindMenuItem, CharacterIdType, LineContainer, ShellDescript, ShellDescriptLine,
+};
+use crate::diagnostic::{Diagnostic, Severity};
+
+use super::{line_number, LintRule};
+
+/// What a line of the binding section defines or refers to.
+enum Binding<'a> {
+    Name(AnimationIdType, &'a String),
+    Default(AnimationIdType),
+    Addid(AnimationIdType),
+    Option(&'a String),
+    Menuitem(u32, &'a BindMenuItem),
+}
+
+/// Reads a line of the binding section with the scope it belongs to,
+/// where `sakura.` and `char0.` are both scope 0, and `kero.` and `char1.` are both scope 1.
+fn binding(line: &ShellDescriptLine) -> Option<(CharacterIdType, Binding<'_>)> {
+    Some(match line {
+        ShellDescriptLine::SakuraBindgroupName(v) => (0, Binding::Name(*v.id(), v.category())),
+        ShellDescriptLine::KeroBindgroupName(v) => (1, Binding::Name(*v.id(), v.category())),
+        ShellDescriptLine::CharBindgroupName(c, v) => (*c, Binding::Name(*v.id(), v.category())),
+        ShellDescriptLine::SakuraBindgroupDefault(id, _) => (0, Binding::Default(*id)),
+        ShellDescriptLine::KeroBindgroupDefault(id, _) => (1, Binding::Default(*id)),
+        ShellDescriptLine::CharBindgroupDefault(c, id, _) => (*c, Binding::Default(*id)),
+        ShellDescriptLine::SakuraBindgroupAddid(id, _) => (0, Binding::Addid(*id)),
+        ShellDescriptLine::KeroBindgroupAddid(id, _) => (1, Binding::Addid(*id)),
+        ShellDescriptLine::CharBindgroupAddid(c, id, _) => (*c, Binding::Addid(*id)),
+        ShellDescriptLine::SakuraBindoptionGroup(v) => (0, Binding::Option(v.category())),
+        ShellDescriptLine::KeroBindoptionGroup(v) => (1, Binding::Option(v.category())),
+        ShellDescriptLine::CharBindoptionGroup(c, v) => (*c, Binding::Option(v.category())),
+        ShellDescriptLine::SakuraMenuitem(i, v) | ShellDescriptLine::SakuraMenuitemex(i, _, v) => {
+            (0, Binding::Menuitem(*i, v))
+        }
+        ShellDescriptLine::KeroMenuitem(i, v) | ShellDescriptLine::KeroMenuitemex(i, _, v) => {
+            (1, Binding::Menuitem(*i, v))
+        }
+        ShellDescriptLine::CharMenuitem(c, i, v)
+        | ShellDescriptLine::CharMenuitemex(c, i, _, v) => (*c, Binding::Menuitem(*i, v)),
+        _ => return None,
+    })
+}
+
+/// Lines of the binding section with their index and scope.
+fn bindings(
+    descript: &ShellDescript,
+) -> Vec<(usize, &ShellDescriptLine, CharacterIdType, Binding<'_>)> {
+    descript
+        .lines()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| match v {
+            LineContainer::Body(v) => binding(v).map(|(scope, b)| (i, v, scope, b)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Builds a diagnostic pointing at the key or the value of the line at `index`.
+fn at_part(
+    descript: &ShellDescript,
+    index: usize,
+    on_key: bool,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    let span = descript.span(index).map(|v| {
+        let part = if on_key { v.key() } else { v.value() };
+        part.unwrap_or(*v.line())
+    });
+    Diagnostic::new(Severity::Warning, code, message, Some(index), span)
+}
+
+/// `bindgroupN.default`, `bindgroupN.addid`, `menuitemN` or `menuitemexN` referring to
+/// `N` which no `bindgroupN.name` of the same character defines.
+pub struct UndefinedBindgroup;
+
+impl LintRule for UndefinedBindgroup {
+    fn code(&self) -> &'static str {
+        "undefined-bindgroup"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        let bindings = bindings(descript);
+        let defined: BTreeSet<(CharacterIdType, AnimationIdType)> = bindings
+            .iter()
+            .filter_map(|(_, _, scope, v)| match v {
+                Binding::Name(id, _) => Some((*scope, *id)),
+                _ => None,
+            })
+            .collect();
+
+        for (index, line, scope, binding) in bindings.iter() {
+            let (id, on_key) = match binding {
+                Binding::Default(id) | Binding::Addid(id) => (*id, true),
+                Binding::Menuitem(_, BindMenuItem::Id(id)) => (*id, false),
+                _ => continue,
+            };
+            if !defined.contains(&(*scope, id)) {
+                diagnostics.push(at_part(
+                    descript,
+                    *index,
+                    on_key,
+                    self.code(),
+                    format!(
+                        "{} refers to bindgroup{} which has no bindgroup{}.name",
+                        line.key(),
+                        id,
+                        id
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// `bindoptionN.group` for a category which no `bindgroupN.name` of the same character has.
+pub struct UnmatchedBindoption;
+
+impl LintRule for UnmatchedBindoption {
+    fn code(&self) -> &'static str {
+        "unmatched-bindoption"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        let bindings = bindings(descript);
+        let categories: BTreeSet<(CharacterIdType, &String)> = bindings
+            .iter()
+            .filter_map(|(_, _, scope, v)| match v {
+                Binding::Name(_, category) => Some((*scope, *category)),
+                _ => None,
+            })
+            .collect();
+
+        for (index, line, scope, binding) in bindings.iter() {
+            if let Binding::Option(category) = binding {
+                if !categories.contains(&(*scope, *category)) {
+                    diagnostics.push(at_part(
+                        descript,
+                        *index,
+                        false,
+                        self.code(),
+                        format!(
+                            "{} is for category {} which no bindgroup has",
+                            line.key(),
+                            category
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// `menuitemN` or `menuitemexN` given more than once for the same `N` of a character.
+///
+/// This includes `menuitemN` together with `menuitemexN`, as the latter replaces the former.
+pub struct DuplicateMenuitem;
+
+impl LintRule for DuplicateMenuitem {
+    fn code(&self) -> &'static str {
+        "duplicate-menuitem"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        let mut seen: BTreeMap<(CharacterIdType, u32), usize> = BTreeMap::new();
+        for (index, line, scope, binding) in bindings(descript).iter() {
+            if let Binding::Menuitem(i, _) = binding {
+                match seen.get(&(*scope, *i)) {
+                    Some(first) => diagnostics.push(at_part(
+                        descript,
+                        *index,
+                        true,
+                        self.code(),
+                        format!(
+                            "{} uses menuitem number {} already used on line {}",
+                            line.key(),
+                            i,
+                            line_number(descript, *first)
+                        ),
+                    )),
+                    None => {
+                        seen.insert((*scope, *i), *index);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `menuitemN` and `menuitemexN` of a character not numbered continuously from 0.
+pub struct MenuitemGap;
+
+impl LintRule for MenuitemGap {
+    fn code(&self) -> &'static str {
+        "menuitem-gap"
+    }
+
+    fn check(&self, descript: &ShellDescript, diagnostics: &mut Vec<Diagnostic>) {
+        let mut numbers: BTreeMap<CharacterIdType, BTreeMap<u32, (usize, String)>> =
+            BTreeMap::new();
+        for (index, line, scope, binding) in bindings(descript).iter() {
+            if let Binding::Menuitem(i, _) = binding {
+                numbers
+                    .entry(*scope)
+                    .or_default()
+                    .entry(*i)
+                    .or_insert((*index, line.key()));
+            }
+        }
+
+        let mut found = Vec::new();
+        for indexes in numbers.values() {
+            let mut expected = 0;
+            for (i, (index, key)) in indexes.iter() {
+                if *i != expected {
+                    let missing = if *i == expected + 1 {
+                        expected.to_string()
+                    } else {
+                        format!("{} to {}", expected, i - 1)
+                    };
+                    found.push((*index, key.clone(), missing));
+                }
+                expected = i + 1;
+            }
+        }
+        found.sort_by_key(|(index, _, _)| *index);
+        for (index, key, missing) in found.into_iter() {
+            diagnostics.push(at_part(
+                descript,
+                index,
+                true,
+                self.code(),
+                format!("menu index {} is missing before {}", missing, key),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ast::{Position, Span};
+    use crate::lint::Linter;
+    use crate::parse::parse;
+
+    const CASE: &str = r#"charset,UTF-8
+sakura.bindgroup0.name,帽子,麦わら
+char0.bindgroup1.name,服,エプロン
+sakura.bindgroup12.default,1
+char0.bindgroup1.addid,10
+kero.bindgroup0.addid,10
+sakura.bindoption0.group,帽子,mustselect
+sakura.bindoption1.group,靴,multiple
+kero.bindoption0.group,帽子,multiple
+sakura.menuitem0,1
+sakura.menuitem1,15
+sakura.menuitemex1,帽子,0
+sakura.menuitem4,-
+kero.menuitem1,-
+"#;
+
+    fn check<R: LintRule + 'static>(rule: R) -> Vec<Diagnostic> {
+        Linter::empty().rule(rule).check(&parse(CASE).unwrap())
+    }
+
+    mod undefined_bindgroup {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = check(UndefinedBindgroup);
+            let indexes: Vec<usize> = result.iter().map(|v| v.index().unwrap()).collect();
+            assert_eq!(indexes, vec![3, 5, 10]);
+            assert_eq!(
+                result[0].message(),
+                "sakura.bindgroup12.default refers to bindgroup12 which has no bindgroup12.name"
+            );
+            assert_eq!(
+                result[0].span(),
+                &Some(Span::new(
+                    Position::new(93, 4, 1),
+                    Position::new(119, 4, 27)
+                ))
+            );
+            assert_eq!(
+                result[2].span().map(|v| *v.start()),
+                Some(Position::new(329, 11, 18))
+            );
+        }
+    }
+
+    mod unmatched_bindoption {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = check(UnmatchedBindoption);
+            let indexes: Vec<usize> = result.iter().map(|v| v.index().unwrap()).collect();
+            assert_eq!(indexes, vec![7, 8]);
+            assert_eq!(
+                result[0].message(),
+                "sakura.bindoption1.group is for category 靴 which no bindgroup has"
+            );
+        }
+    }
+
+    mod duplicate_menuitem {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = check(DuplicateMenuitem);
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].index(), &Some(11));
+            assert_eq!(
+                result[0].message(),
+                "sakura.menuitemex1 uses menuitem number 1 already used on line 11"
+            );
+        }
+    }
+
+    mod menuitem_gap {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = check(MenuitemGap);
+            let messages: Vec<&str> = result.iter().map(|v| v.message().as_str()).collect();
+            assert_eq!(
+                messages,
+                vec![
+                    "menu index 2 to 3 is missing before sakura.menuitem4",
+                    "menu index 0 is missing before kero.menuitem1",
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Loads descript.txt of a shell from the file system.
+
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::ast::ShellDescript;
+use crate::parse::{decode_bytes, parse_lossless, ParseError};
+
+/// Name of the file [`load_shell_dir`] reads.
+pub const DESCRIPT_FILE_NAME: &str = "descript.txt";
+
+/// Options of [`load_shell_dir_with_options`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadOptions {
+    /// Finds descript.txt regardless of case, e.g. `Descript.txt`.
+    pub ignore_case: bool,
+}
+
+/// Error from loading descript.txt.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be found or read.
+    Io(PathBuf, io::Error),
+    /// The file could not be decoded with its `charset`.
+    Decode(PathBuf, String),
+    /// The decoded text could not be parsed.
+    Parse(PathBuf, ParseError),
+}
+
+/// Loads descript.txt in the shell directory `dir`, e.g. `ghost/master/shell/master`.
+///
+/// # Example
+///
+/// ```
+/// use std::path::PathBuf;
+/// use shell_parser_descript_rs::load_shell_dir;
+///
+/// let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/descript");
+/// let shell_descript = load_shell_dir(&dir).unwrap();
+/// assert_eq!(
+///     shell_descript.resolve().craftmanw(),
+///     &Some("うか犬".to_string())
+/// );
+/// ```
+pub fn load_shell_dir<P: AsRef<Path>>(dir: P) -> Result<ShellDescript, LoadError> {
+    load_shell_dir_with_options(dir, &LoadOptions::default())
+}
+
+/// Loads descript.txt in the shell directory `dir` with `options`.
+pub fn load_shell_dir_with_options<P: AsRef<Path>>(
+    dir: P,
+    options: &LoadOptions,
+) -> Result<ShellDescript, LoadError> {
+    let path = find_descript(dir.as_ref(), options)?;
+    ShellDescript::from_path(path)
+}
+
+/// Finds descript.txt in `dir`, preferring the exact name when ignoring case.
+pub fn find_descript(dir: &Path, options: &LoadOptions) -> Result<PathBuf, LoadError> {
+    let exact = dir.join(DESCRIPT_FILE_NAME);
+    if exact.is_file() || !options.ignore_case {
+        return Ok(exact);
+    }
+
+    let entries = fs::read_dir(dir).map_err(|e| LoadError::Io(dir.to_path_buf(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| LoadError::Io(dir.to_path_buf(), e))?;
+        let is_descript = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|v| v.eq_ignore_ascii_case(DESCRIPT_FILE_NAME));
+        if is_descript && entry.path().is_file() {
+            return Ok(entry.path());
+        }
+    }
+    Err(LoadError::Io(
+        exact,
+        io::Error::new(io::ErrorKind::NotFound, "descript.txt is not found"),
+    ))
+}
+
+impl ShellDescript {
+    /// Reads, decodes and parses the descript.txt file at `path`.
+    ///
+    /// The result keeps the original text of each line as [`parse_lossless`] does.
+    ///
+    /// [`parse_lossless`]: crate::parse::parse_lossless
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<ShellDescript, LoadError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+        let text = decode_bytes(&bytes).map_err(|e| LoadError::Decode(path.to_path_buf(), e))?;
+        parse_lossless(&text).map_err(|e| LoadError::Parse(path.to_path_buf(), e))
+    }
+}
+
+impl LoadError {
+    /// Path of the file or directory the error is about.
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            LoadError::Io(v, _) | LoadError::Decode(v, _) | LoadError::Parse(v, _) => v,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            LoadError::Decode(path, e) => write!(f, "{}: {}", path.display(), e),
+            LoadError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(_, e) => Some(e),
+            LoadError::Decode(_, _) => None,
+            LoadError::Parse(_, e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_support::shell_dir;
+
+    fn test_target() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/descript")
+    }
+
+    mod load_shell_dir {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result = load_shell_dir(test_target()).unwrap();
+            let expected = fs::read(test_target().join(DESCRIPT_FILE_NAME)).unwrap();
+            assert_eq!(
+                result.to_string(),
+                decode_bytes(&expected).unwrap().to_string()
+            );
+        }
+
+        #[test]
+        fn success_when_last_line_is_comment() {
+            let dir = shell_dir("load-comment", &[]);
+            fs::write(
+                dir.join(DESCRIPT_FILE_NAME),
+                "charset,UTF-8\r\nname,master\r\n// comment",
+            )
+            .unwrap();
+
+            let result = load_shell_dir(&dir).unwrap();
+            assert_eq!(result.resolve().name(), &Some("master".to_string()));
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let dir = shell_dir("load-invalid", &[]);
+
+            let result = load_shell_dir(&dir).unwrap_err();
+            assert!(matches!(&result, LoadError::Io(_, e) if e.kind() == io::ErrorKind::NotFound));
+            assert_eq!(result.path(), &dir.join(DESCRIPT_FILE_NAME));
+
+            fs::write(
+                dir.join(DESCRIPT_FILE_NAME),
+                b"charset,UTF-8\r\nname,\xff\r\n",
+            )
+            .unwrap();
+            let result = load_shell_dir(&dir).unwrap_err();
+            assert!(matches!(result, LoadError::Decode(_, _)));
+
+            fs::write(
+                dir.join(DESCRIPT_FILE_NAME),
+                "charset,UTF-8\r\nseriko.use_self_alpha,",
+            )
+            .unwrap();
+            let result = load_shell_dir(&dir).unwrap_err();
+            match &result {
+                LoadError::Parse(_, e) => assert_eq!(*e.position().line(), 2),
+                _ => panic!("{:?}", result),
+            }
+            assert!(result.source().is_some());
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod load_shell_dir_with_options {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let dir = shell_dir("load-ignore-case", &[]);
+            fs::write(dir.join("Descript.TXT"), "charset,UTF-8\r\nname,master\r\n").unwrap();
+
+            let options = LoadOptions { ignore_case: true };
+            let result = load_shell_dir_with_options(&dir, &options).unwrap();
+            assert_eq!(result.resolve().name(), &Some("master".to_string()));
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
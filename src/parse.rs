@@ -7,7 +7,7 @@ use std::borrow::Cow;
 use nom::{
     branch::alt,
     bytes::complete::{is_not, tag},
-    combinator::{eof, map, not, opt},
+    combinator::{eof, map, not, opt, verify},
     multi::many0,
     sequence::{preceded, terminated, tuple},
     IResult,
@@ -17,17 +17,30 @@ use shell_parser_common_rs::{
     ShellParseError,
 };
 
-use crate::ast::{LineContainer, ShellDescript, ShellDescriptLine};
+use crate::ast::{LineContainer, LineSource, Newline, Position, ShellDescript, ShellDescriptLine};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lint::{InvalidValue, Linter, NonSlashComment, UnknownKey};
 
-use self::parts::{comment_line, empty_line, newline_body, parse_line_func};
+use self::parts::{
+    comment_line, empty_line, invalid_line, newline_body, parse_line_func, unknown_line,
+    with_source,
+};
+
+pub use self::error::ParseError;
+pub use self::options::ParseOptions;
+pub use self::surfaces::{scan_animations, scan_animations_bytes, AnimationDefinition};
 
 mod alpha;
 mod balloon_representation;
 mod base;
 mod binding;
+mod error;
+pub(crate) mod keys;
 mod menu;
+mod options;
 mod parts;
 mod shell_representation;
+mod surfaces;
 
 /// Decodes bytes to `Cow<'a, str>` from specified charset.
 ///
@@ -66,7 +79,7 @@ pub fn decode_bytes<'a>(input: &'a [u8]) -> Result<Cow<'a, str>, String> {
     }
 }
 
-fn parse_for_decode<'a>(input: &'a str) -> IResult<&'a str, Charset, ShellParseError> {
+pub(crate) fn parse_for_decode<'a>(input: &'a str) -> IResult<&'a str, Charset, ShellParseError> {
     preceded(
         many0(tuple((
             not(parse_for_decode_charset),
@@ -131,21 +144,269 @@ fn parse_for_decode_charset<'a>(input: &'a str) -> IResult<&'a str, Charset, She
 ///     .collect();
 /// assert_eq!(shell_descript_bodies.len(), 15);
 /// ```
-pub fn parse<'a>(input: &'a str) -> Result<ShellDescript, nom::Err<ShellParseError>> {
-    shell_descript(input).map(|(_, v)| v)
+///
+/// A last line without a terminator which is neither a setting nor a `//` comment makes an error.
+///
+/// ```
+/// use shell_parser_descript_rs::parse;
+///
+/// let case = "charset,UTF-8\nseriko.use_self_alpha,";
+///
+/// let error = parse(case).unwrap_err();
+/// assert_eq!(*error.position().line(), 2);
+/// assert_eq!(*error.position().column(), 23);
+/// assert_eq!(error.key(), &Some("seriko.use_self_alpha".to_string()));
+/// ```
+pub fn parse<'a>(input: &'a str) -> Result<ShellDescript, ParseError> {
+    shell_descript(input)
+        .map(|(_, v)| v)
+        .map_err(|_| parse_error(input))
+}
+
+/// Parses a [`ShellDescript`] from `&str`, keeping the original text and terminator of each line.
+///
+/// Writing the result of an unmodified document gives back the input as is.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::parse_lossless;
+///
+/// let case = "charset,UTF-8\nsakura.defaultx,-0020\r\n\n// comment";
+///
+/// let shell_descript = parse_lossless(case).unwrap();
+/// assert_eq!(shell_descript.to_string(), case);
+/// ```
+pub fn parse_lossless<'a>(input: &'a str) -> Result<ShellDescript, ParseError> {
+    shell_descript_lossless(input)
+        .map(|(_, v)| v)
+        .map_err(|_| parse_error(input))
+}
+
+/// Parses a [`ShellDescript`] from `&str` with `options`, returning all the errors found.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::{parse_with_options, ParseOptions};
+///
+/// let case = "charset,UTF-8\nnot comment\n// comment\nmenu.font.height,big\n";
+///
+/// assert!(parse_with_options(case, &ParseOptions::default()).is_ok());
+///
+/// let options = ParseOptions {
+///     strict: true,
+///     ..Default::default()
+/// };
+/// let errors = parse_with_options(case, &options).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(*errors[0].position().line(), 2);
+/// assert_eq!(*errors[1].position().line(), 4);
+/// ```
+pub fn parse_with_options<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> Result<ShellDescript, Vec<ParseError>> {
+    parse_with_warnings(input, options).map(|(v, _)| v)
+}
+
+/// Parses a [`ShellDescript`] from `&str` with `options`, also returning a warning for each
+/// normalisation [`ParseOptions::trim_whitespace`] or [`ParseOptions::ignore_case`] applies,
+/// including to lines which are read as settings without it.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::{parse_with_warnings, ParseOptions, ShellDescriptLine};
+///
+/// let case = "charset,UTF-8\nType,Shell\nmenu.font.height,12 \t\n";
+/// let options = ParseOptions {
+///     trim_whitespace: true,
+///     ignore_case: true,
+///     ..Default::default()
+/// };
+///
+/// let (shell_descript, warnings) = parse_with_warnings(case, &options).unwrap();
+/// assert_eq!(shell_descript.get("type"), Some(&ShellDescriptLine::Type));
+/// assert_eq!(
+///     shell_descript.get("menu.font.height"),
+///     Some(&ShellDescriptLine::MenuFontHeight(12))
+/// );
+/// assert_eq!(warnings.len(), 3);
+/// ```
+pub fn parse_with_warnings<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> Result<(ShellDescript, Vec<Diagnostic>), Vec<ParseError>> {
+    let (mut lines, _) = recover_lines(input);
+    let mut notes = Vec::new();
+    for (index, (line, source)) in lines.iter_mut().enumerate() {
+        if *line == LineContainer::EmptyLine {
+            continue;
+        }
+        // A setting already read is replaced only if a normalisation changes it.
+        if let Some((body, v)) = options::normalize(source.text(), options) {
+            if !v.is_empty() || !matches!(line, LineContainer::Body(_)) {
+                *line = LineContainer::Body(body);
+                notes.extend(v.into_iter().map(|v| (index, v)));
+            }
+        }
+    }
+    // A `//` comment without a terminator is not broken, even in strict mode.
+    let broken = is_last_broken(&lines);
+    let descript = ShellDescript::with_sources(lines);
+
+    let mut errors = Vec::new();
+    if options.strict {
+        errors.extend(options::strict_errors(&descript));
+    }
+    let last = descript.lines().len();
+    if broken && !errors.iter().any(|v| *v.position().line() == last) {
+        errors.push(parse_error(input));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let warnings = notes
+        .into_iter()
+        .map(|(index, note)| note.into_diagnostic(index, descript.span(index)))
+        .collect();
+    Ok((descript.forget_sources(), warnings))
+}
+
+/// Parses a [`ShellDescript`] from `&str`, rejecting every non-empty line which is
+/// neither a known setting nor a `//` comment.
+///
+/// Same as [`parse_with_options`] with [`ParseOptions::strict`] set.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+pub fn parse_strict<'a>(input: &'a str) -> Result<ShellDescript, Vec<ParseError>> {
+    parse_with_options(
+        input,
+        &ParseOptions {
+            strict: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// Parses a [`ShellDescript`] from `&str`, never failing.
+///
+/// Lines which cannot be read are kept as [`LineContainer::Invalid`], [`LineContainer::Unknown`]
+/// or [`LineContainer::CommentLine`], and reported as diagnostics with their spans,
+/// where a [`LineContainer::CommentLine`] is reported only if it does not start with `//`.
+/// A broken last line without a terminator, which makes [`parse`] fail, is also reported
+/// as an error.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::{parse_recovering, LineContainer};
+///
+/// let case = "charset,UTF-8\nsakura.defaultx,10\nseriko.use_self_alpha,";
+///
+/// let (shell_descript, diagnostics) = parse_recovering(case);
+/// assert_eq!(shell_descript.lines().len(), 3);
+/// assert!(matches!(shell_descript.lines()[2], LineContainer::Invalid { .. }));
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].code(), "invalid-value");
+/// assert_eq!(diagnostics[0].index(), &Some(2));
+/// ```
+pub fn parse_recovering<'a>(input: &'a str) -> (ShellDescript, Vec<Diagnostic>) {
+    let (lines, unterminated) = recover_lines(input);
+    let descript = ShellDescript::with_sources(lines);
+
+    let mut diagnostics = Linter::empty()
+        .rule(InvalidValue)
+        .rule(UnknownKey)
+        .rule(NonSlashComment)
+        .check(&descript);
+    diagnostics.sort_by_key(|v| *v.index());
+
+    // Other broken lines are reported above, and a `//` comment is not broken.
+    let last = descript.lines().len().checked_sub(1);
+    let broken_text = last.filter(|index| match &descript.lines()[*index] {
+        LineContainer::CommentLine(v) => unterminated && !v.starts_with("//"),
+        _ => false,
+    });
+    if let Some(index) = broken_text {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            "unterminated-line",
+            "last line has no newline and is neither a setting nor a // comment".to_string(),
+            Some(index),
+            descript.span(index).map(|v| *v.line()),
+        ));
+    }
+
+    (descript.forget_sources(), diagnostics)
+}
+
+/// Reads all the lines, and tells whether the last one is broken as [`is_last_broken`] says.
+fn recover_lines<'a>(input: &'a str) -> (Vec<(LineContainer, LineSource)>, bool) {
+    let (_, lines) = parse_lines(input).unwrap_or((input, Vec::new()));
+    let broken = is_last_broken(&lines);
+    (lines, broken)
+}
+
+/// Tells whether the last line has no terminator and is neither a setting nor a `//` comment,
+/// which [`parse`] rejects.
+fn is_last_broken(lines: &[(LineContainer, LineSource)]) -> bool {
+    lines.last().is_some_and(|(line, source)| {
+        *source.newline() == Newline::Eof
+            && match line {
+                LineContainer::Body(_) | LineContainer::EmptyLine => false,
+                LineContainer::CommentLine(v) => !v.starts_with("//"),
+                _ => true,
+            }
+    })
+}
+
+/// Builds the error for the broken last line.
+fn parse_error(input: &str) -> ParseError {
+    let (_, lines) = parse_lines(input).unwrap_or((input, Vec::new()));
+    let text = lines
+        .last()
+        .map(|(_, v)| v.text().as_str())
+        .unwrap_or_default();
+    let start = Position::new(input.len() - text.len(), lines.len().max(1), 1);
+
+    ParseError::at_line(start, text)
 }
 
 fn shell_descript<'a>(input: &'a str) -> IResult<&'a str, ShellDescript, ShellParseError> {
-    map(terminated(parse_lines, eof), |v| ShellDescript::new(v))(input)
+    map(shell_descript_lossless, |v| v.forget_sources())(input)
+}
+
+fn shell_descript_lossless<'a>(input: &'a str) -> IResult<&'a str, ShellDescript, ShellParseError> {
+    map(
+        verify(terminated(parse_lines, eof), |v: &Vec<_>| {
+            !is_last_broken(v)
+        }),
+        ShellDescript::with_sources,
+    )(input)
 }
 
-fn parse_lines<'a>(input: &'a str) -> IResult<&'a str, Vec<LineContainer>, ShellParseError> {
-    many0(parse_line)(input)
+fn parse_lines<'a>(
+    input: &'a str,
+) -> IResult<&'a str, Vec<(LineContainer, LineSource)>, ShellParseError> {
+    many0(with_source(parse_line))(input)
 }
 
 fn parse_line<'a>(input: &'a str) -> IResult<&'a str, LineContainer, ShellParseError> {
     alt((
         parse_line_func(shell_descript_line),
+        invalid_line,
+        unknown_line,
         comment_line,
         empty_line,
     ))(input)
@@ -371,6 +632,53 @@ kero.balloon.alignment,none
             assert_eq!(bodies.len(), 15);
         }
 
+        #[test]
+        fn success_when_valid_str_with_spans() {
+            use crate::ast::{LineSpan, Position, Span};
+
+            let case = "charset,UTF-8\r\n\n// comment\rcraftmanw,うか犬";
+            let result = parse(case).unwrap();
+            assert!(result.sources().iter().all(|v| v.is_none()));
+            assert_eq!(
+                result.span(0),
+                Some(&LineSpan::new(
+                    Span::new(Position::new(0, 1, 1), Position::new(13, 1, 14)),
+                    Some(Span::new(Position::new(0, 1, 1), Position::new(7, 1, 8))),
+                    Some(Span::new(Position::new(8, 1, 9), Position::new(13, 1, 14))),
+                    Span::new(Position::new(13, 1, 14), Position::new(15, 1, 16))
+                ))
+            );
+            assert_eq!(
+                result.span(2),
+                Some(&LineSpan::new(
+                    Span::new(Position::new(16, 3, 1), Position::new(26, 3, 11)),
+                    None,
+                    None,
+                    Span::new(Position::new(26, 3, 11), Position::new(27, 3, 12))
+                ))
+            );
+            assert_eq!(
+                result.span(3),
+                Some(&LineSpan::new(
+                    Span::new(Position::new(27, 4, 1), Position::new(46, 4, 14)),
+                    Some(Span::new(Position::new(27, 4, 1), Position::new(36, 4, 10))),
+                    Some(Span::new(
+                        Position::new(37, 4, 11),
+                        Position::new(46, 4, 14)
+                    )),
+                    Span::new(Position::new(46, 4, 14), Position::new(46, 4, 14))
+                ))
+            );
+            assert_eq!(result.line_index_at(30), Some(3));
+            assert_eq!(result.line_index_at(13), Some(0));
+            assert_eq!(result.line_index_at(14), Some(0));
+            assert_eq!(result.line_index_at(15), Some(1));
+            assert_eq!(result.line_index_at(26), Some(2));
+            assert_eq!(result.line_index_at(46), Some(3));
+            assert_eq!(result.line_index_at(47), None);
+            assert_eq!(result.span(4), None);
+        }
+
         #[test]
         fn failed_when_invalid_str() {
             let case = "seriko.use_self_alpha,";
@@ -378,6 +686,368 @@ kero.balloon.alignment,none
         }
     }
 
+    mod parse_error {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\nmenu.frame.color.r,300\r\nmenu.font.height,";
+            let result = parse_error(case);
+            assert_eq!(result.position(), &Position::new(56, 3, 18));
+            assert_eq!(result.text(), "menu.font.height,");
+            assert_eq!(result.key(), &Some("menu.font.height".to_string()));
+            assert_eq!(result.expected(), "non-negative integer");
+
+            let case = "not comment";
+            let result = parse_error(case);
+            assert_eq!(result.position(), &Position::new(0, 1, 1));
+            assert_eq!(result.key(), &None);
+        }
+    }
+
+    mod parse_strict {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\n\r\n// comment\r\nsakura.defaultx,10";
+            let result = parse_strict(case).unwrap();
+            assert_eq!(result, parse(case).unwrap());
+
+            let case = "charset,UTF-8\r\nsakura.defaultx,10\r\n// end";
+            let result = parse_strict(case).unwrap();
+            assert_eq!(
+                result.lines()[2],
+                LineContainer::CommentLine("// end".to_string())
+            );
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,UTF-8\r\nType,Shell\r\nsakura.balloon.alignment, left\r\nseriko.use_self_alpha,";
+            let result = parse_strict(case).unwrap_err();
+            assert_eq!(result.len(), 3);
+            assert_eq!(result[0].position(), &Position::new(15, 2, 1));
+            assert_eq!(result[1].position(), &Position::new(52, 3, 26));
+            assert_eq!(result[2].position(), &Position::new(81, 4, 23));
+            assert_eq!(result[2].key(), &Some("seriko.use_self_alpha".to_string()));
+        }
+    }
+
+    mod parse_with_options {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\nnot comment\r\n";
+            let result = parse_with_options(case, &ParseOptions::default()).unwrap();
+            assert_eq!(result, parse(case).unwrap());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,UTF-8\r\nseriko.use_self_alpha,";
+            let result = parse_with_options(case, &ParseOptions::default()).unwrap_err();
+            assert_eq!(result, vec![parse(case).unwrap_err()]);
+        }
+    }
+
+    mod parse_with_warnings {
+        use super::*;
+
+        use crate::ast::{BalloonPosition, BindOption, SurfacePosition};
+
+        fn tolerant() -> ParseOptions {
+            ParseOptions {
+                trim_whitespace: true,
+                ignore_case: true,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"Charset,utf-8
+sakura.balloon.alignment, left
+ Type , Shell
+seriko.alignmenttodesktop,Free
+sakura.bindoption0.group,帽子,MustSelect+multiple
+Menu,Hidden
+menu.font.name,ＭＳ ゴシック
+menu.font.height,12	"#;
+            let (result, warnings) = parse_with_warnings(case, &tolerant()).unwrap();
+            let bodies: Vec<&ShellDescriptLine> = result
+                .lines()
+                .iter()
+                .filter_map(|v| match v {
+                    LineContainer::Body(v) => Some(v),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                bodies,
+                vec![
+                    &ShellDescriptLine::Charset(Charset::UTF8),
+                    &ShellDescriptLine::SakuraBalloonAlignment(BalloonPosition::Left),
+                    &ShellDescriptLine::Type,
+                    &ShellDescriptLine::SerikoAlignmenttodesktop(SurfacePosition::Free),
+                    &ShellDescriptLine::SakuraBindoptionGroup(BindOption::new(
+                        0,
+                        "帽子".to_string(),
+                        true,
+                        true
+                    )),
+                    &ShellDescriptLine::MenuHidden,
+                    &ShellDescriptLine::MenuFontName("ＭＳ ゴシック".to_string()),
+                    &ShellDescriptLine::MenuFontHeight(12),
+                ]
+            );
+
+            let codes: Vec<(usize, &str)> = warnings
+                .iter()
+                .map(|v| (v.index().unwrap(), v.code().as_str()))
+                .collect();
+            assert_eq!(
+                codes,
+                vec![
+                    (0, "normalized-case"),
+                    (0, "normalized-case"),
+                    (1, "trimmed-whitespace"),
+                    (2, "trimmed-whitespace"),
+                    (2, "normalized-case"),
+                    (2, "trimmed-whitespace"),
+                    (2, "normalized-case"),
+                    (3, "normalized-case"),
+                    (4, "normalized-case"),
+                    (5, "normalized-case"),
+                    (5, "normalized-case"),
+                    (7, "trimmed-whitespace"),
+                ]
+            );
+            assert!(warnings.iter().all(|v| *v.severity() == Severity::Warning));
+            assert_eq!(
+                warnings[1].span().map(|v| *v.start()),
+                Some(Position::new(8, 1, 9))
+            );
+            assert_eq!(warnings[1].message(), "value `utf-8` is read as `UTF-8`");
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,UTF-8\nType,Shell\nmenu.font.height,12 ";
+
+            let result = parse_with_warnings(case, &ParseOptions::default()).unwrap_err();
+            assert_eq!(result, vec![parse(case).unwrap_err()]);
+
+            let result = parse_with_warnings(
+                case,
+                &ParseOptions {
+                    trim_whitespace: true,
+                    ..Default::default()
+                },
+            );
+            assert!(result.is_ok());
+
+            let result = parse_with_warnings(
+                case,
+                &ParseOptions {
+                    strict: true,
+                    trim_whitespace: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].text(), "Type,Shell");
+        }
+
+        #[test]
+        fn success_when_text_value() {
+            let case = "charset,UTF-8\nname,master \nsakura.name, さくら\nmenu.font.name, MS Gothic\ncraftman, ukadog, and friends\n";
+            let options = ParseOptions {
+                trim_whitespace: true,
+                ..Default::default()
+            };
+            let (result, warnings) = parse_with_warnings(case, &options).unwrap();
+            assert_eq!(
+                result.lines()[1..],
+                [
+                    LineContainer::Body(ShellDescriptLine::Name("master".to_string())),
+                    LineContainer::Body(ShellDescriptLine::SakuraName("さくら".to_string())),
+                    LineContainer::Body(ShellDescriptLine::MenuFontName("MS Gothic".to_string())),
+                    LineContainer::Body(ShellDescriptLine::Craftman(
+                        "ukadog, and friends".to_string()
+                    )),
+                ]
+            );
+            let codes: Vec<(usize, &str)> = warnings
+                .iter()
+                .map(|v| (v.index().unwrap(), v.code().as_str()))
+                .collect();
+            assert_eq!(
+                codes,
+                vec![
+                    (1, "trimmed-whitespace"),
+                    (2, "trimmed-whitespace"),
+                    (3, "trimmed-whitespace"),
+                    (4, "trimmed-whitespace"),
+                ]
+            );
+
+            let (_, warnings) = parse_with_warnings(case, &ParseOptions::default()).unwrap();
+            assert!(warnings.is_empty());
+        }
+    }
+
+    mod parse_recovering {
+        use super::*;
+
+        use crate::ast::Span;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\n// comment\r\nsakura.defaultx,10";
+            let (result, diagnostics) = parse_recovering(case);
+            assert_eq!(result, parse(case).unwrap());
+            assert_eq!(diagnostics, vec![]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,UTF-8\r\nmenu.font.height,big\r\nfoo.bar,1\r\nnot comment";
+            let (result, diagnostics) = parse_recovering(case);
+            assert_eq!(result.lines().len(), 4);
+            assert_eq!(
+                result.lines()[3],
+                LineContainer::CommentLine("not comment".to_string())
+            );
+            assert_eq!(result.to_string(), format!("{}\r\n", case));
+            assert_eq!(diagnostics.len(), 4);
+
+            assert_eq!(diagnostics[0].code(), "invalid-value");
+            assert_eq!(diagnostics[0].severity(), &Severity::Error);
+            assert_eq!(
+                diagnostics[0].message(),
+                "expected non-negative integer for menu.font.height"
+            );
+            assert_eq!(
+                diagnostics[0].span(),
+                &Some(Span::new(
+                    Position::new(32, 2, 18),
+                    Position::new(35, 2, 21)
+                ))
+            );
+
+            assert_eq!(diagnostics[1].code(), "unknown-key");
+            assert_eq!(diagnostics[1].severity(), &Severity::Warning);
+            assert_eq!(
+                diagnostics[1].span(),
+                &Some(Span::new(Position::new(37, 3, 1), Position::new(44, 3, 8)))
+            );
+
+            assert_eq!(diagnostics[2].code(), "non-slash-comment");
+            assert_eq!(diagnostics[2].index(), &Some(3));
+            assert_eq!(diagnostics[3].code(), "unterminated-line");
+            assert_eq!(diagnostics[3].index(), &Some(3));
+        }
+
+        #[test]
+        fn failed_when_text_in_middle() {
+            let case = "charset,UTF-8\r\nnot comment\r\nsakura.defaultx,10\r\n";
+            let (result, diagnostics) = parse_recovering(case);
+            assert_eq!(result, parse(case).unwrap());
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].code(), "non-slash-comment");
+            assert_eq!(diagnostics[0].severity(), &Severity::Warning);
+            assert_eq!(diagnostics[0].index(), &Some(1));
+        }
+
+        #[test]
+        fn success_when_unterminated_comment() {
+            let case = "charset,UTF-8\r\nsakura.defaultx,10\r\n// x";
+            let (result, diagnostics) = parse_recovering(case);
+            assert_eq!(result, parse(case).unwrap());
+            assert_eq!(diagnostics, vec![]);
+        }
+
+        #[test]
+        fn success_when_empty() {
+            let (result, diagnostics) = parse_recovering("");
+            assert_eq!(result.lines().len(), 0);
+            assert_eq!(diagnostics, vec![]);
+        }
+    }
+
+    mod parse_lossless {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,Shift_JIS\r\ntype,shell\n\r//comment\r\nsakura.defaultx,-0020";
+            let result = parse_lossless(case).unwrap();
+            assert_eq!(result.lines().len(), 5);
+            assert_eq!(result.to_string(), case);
+
+            let case = "charset,UTF-8\r\nname,x\r\n// end";
+            let result = parse_lossless(case).unwrap();
+            assert_eq!(
+                result.lines()[2],
+                LineContainer::CommentLine("// end".to_string())
+            );
+            assert_eq!(result.to_string(), case);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "seriko.use_self_alpha,";
+            assert!(parse_lossless(case).is_err());
+
+            let case = "name,x\r\nnot comment";
+            assert!(parse_lossless(case).is_err());
+        }
+    }
+
+    mod shell_descript_lossless {
+        use crate::ast::{LineSource, Newline};
+
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "sakura.defaultx,-0020\n\r\nmenu.font.height,12";
+            let (remain, result) = shell_descript_lossless(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result.lines(),
+                &vec![
+                    LineContainer::Body(ShellDescriptLine::SakuraDefaultx(-20)),
+                    LineContainer::EmptyLine,
+                    LineContainer::Body(ShellDescriptLine::MenuFontHeight(12)),
+                ]
+            );
+            assert_eq!(
+                result.sources(),
+                &vec![
+                    Some(LineSource::new(
+                        "sakura.defaultx,-0020".to_string(),
+                        Newline::Lf
+                    )),
+                    Some(LineSource::new("".to_string(), Newline::CrLf)),
+                    Some(LineSource::new(
+                        "menu.font.height,12".to_string(),
+                        Newline::Eof
+                    )),
+                ]
+            );
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "seriko.use_self_alpha,";
+            assert!(shell_descript_lossless(case).is_err());
+        }
+    }
+
     mod shell_descript {
         use super::*;
 
@@ -463,11 +1133,42 @@ kero.balloon.alignment,none
             let (remain, result) = parse_line(case).unwrap();
             assert_eq!(remain, "");
             assert_eq!(result, LineContainer::EmptyLine);
+
+            let case = "sakura.balloon.alignment,center\r\n";
+            let (remain, result) = parse_line(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                LineContainer::Invalid {
+                    key: "sakura.balloon.alignment".to_string(),
+                    value: "center".to_string(),
+                    reason: "expected `none`, `left` or `right`".to_string(),
+                }
+            );
+
+            let case = "sakura.balloon.offsetz,10\r\n";
+            let (remain, result) = parse_line(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                LineContainer::Unknown {
+                    key: "sakura.balloon.offsetz".to_string(),
+                    values: vec!["10".to_string()],
+                }
+            );
+
+            let case = "// sakura.balloon.alignment,center\r\n";
+            let (remain, result) = parse_line(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(
+                result,
+                LineContainer::CommentLine("// sakura.balloon.alignment,center".to_string())
+            );
         }
 
         #[test]
         fn failed_when_invalid_str() {
-            let case = "seriko.use_self_alpha,";
+            let case = "";
             assert!(parse_line(case).is_err());
         }
     }
@@ -0,0 +1,149 @@
+use std::{error::Error, fmt};
+
+use crate::ast::Position;
+
+use super::keys::value_kind;
+
+const EXPECTED_LINE: &str =
+    "a known key, a comment starting with //, or a line ending with a newline";
+const EXPECTED_STRICT_LINE: &str = "a known key, or a comment starting with //";
+
+/// Error from parsing a [`ShellDescript`].
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    position: Position,
+    text: String,
+    key: Option<String>,
+    expected: String,
+}
+
+impl ParseError {
+    pub fn new(
+        position: Position,
+        text: String,
+        key: Option<String>,
+        expected: String,
+    ) -> ParseError {
+        ParseError {
+            position,
+            text,
+            key,
+            expected,
+        }
+    }
+
+    /// Builds an error for the line `text` starting at `start`.
+    ///
+    /// When the key of the line is known, the error points at its value.
+    pub(crate) fn at_line(start: Position, text: &str) -> ParseError {
+        ParseError::at_line_expecting(start, text, EXPECTED_LINE)
+    }
+
+    /// Builds an error for the line `text` rejected by strict parsing.
+    pub(crate) fn at_strict_line(start: Position, text: &str) -> ParseError {
+        ParseError::at_line_expecting(start, text, EXPECTED_STRICT_LINE)
+    }
+
+    fn at_line_expecting(start: Position, text: &str, expected_line: &str) -> ParseError {
+        let known = text
+            .split_once(',')
+            .and_then(|(key, _)| value_kind(key).map(|v| (key, v)));
+
+        match known {
+            Some((key, kind)) => ParseError::new(
+                Position::new(
+                    start.offset() + key.len() + 1,
+                    *start.line(),
+                    start.column() + key.chars().count() + 1,
+                ),
+                text.to_string(),
+                Some(key.to_string()),
+                kind.expected().to_string(),
+            ),
+            None => ParseError::new(start, text.to_string(), None, expected_line.to_string()),
+        }
+    }
+
+    /// Position where the error is found.
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+    /// Text of the offending line.
+    pub fn text(&self) -> &String {
+        &self.text
+    }
+    /// Key which was being parsed, if it is known.
+    pub fn key(&self) -> &Option<String> {
+        &self.key
+    }
+    /// Description of what was expected.
+    pub fn expected(&self) -> &String {
+        &self.expected
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.position.line(),
+            self.position.column(),
+            self.expected
+        )?;
+        if let Some(key) = &self.key {
+            write!(f, " for {}", key)?;
+        }
+        write!(f, " in `{}`", self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod at_line {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "menu.frame.color.r,300";
+            let result = ParseError::at_line(Position::new(10, 2, 1), case);
+            assert_eq!(result.position(), &Position::new(29, 2, 20));
+            assert_eq!(result.key(), &Some("menu.frame.color.r".to_string()));
+            assert_eq!(
+                result.to_string(),
+                "line 2, column 20: expected integer 0-255 for menu.frame.color.r in `menu.frame.color.r,300`"
+            );
+
+            let case = "somethingwrong";
+            let result = ParseError::at_line(Position::new(10, 2, 1), case);
+            assert_eq!(result.position(), &Position::new(10, 2, 1));
+            assert_eq!(result.key(), &None);
+            assert_eq!(result.expected(), EXPECTED_LINE);
+        }
+    }
+
+    mod at_strict_line {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "this is not comment";
+            let result = ParseError::at_strict_line(Position::new(10, 2, 1), case);
+            assert_eq!(
+                result.to_string(),
+                "line 2, column 1: expected a known key, or a comment starting with // in `this is not comment`"
+            );
+
+            let case = "sakura.balloon.alignment, left";
+            let result = ParseError::at_strict_line(Position::new(10, 2, 1), case);
+            assert_eq!(result.position(), &Position::new(35, 2, 26));
+            assert_eq!(result.expected(), "`none`, `left` or `right`");
+        }
+    }
+}
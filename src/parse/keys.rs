@@ -0,0 +1,419 @@
+use std::sync::OnceLock;
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    BalloonPosition, BindGroupNameProps, BindMenuItem, BindMenuVisibility, BindOption,
+    MenuPositionForegroundBackgroundBase, MenuPositionSidebarBase, ShellDescriptLine,
+    SurfacePosition,
+};
+
+/// Kind of value a key takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ValueKind {
+    Charset,
+    Text,
+    Shell,
+    Hidden,
+    ScopeIds,
+    SurfacePosition,
+    DesktopPosition,
+    BalloonPosition,
+    BalloonDontmove,
+    FontSize,
+    Color,
+    Flag,
+    MenuAlignment,
+    SidebarAlignment,
+    BindgroupName,
+    AnimationIds,
+    BindOption,
+    MenuItem,
+    MenuItemex,
+    MenuVisibility,
+}
+
+/// Number put in the sample lines, standing for any number in their keys.
+pub(crate) const ANY: u32 = u32::MAX;
+
+/// A line of each known key, with the kind of value the key takes.
+///
+/// The keys themselves are spelled only in [`ShellDescriptLine::key`].
+fn samples() -> Vec<(ShellDescriptLine, ValueKind)> {
+    use ShellDescriptLine as L;
+
+    let text = String::new;
+    let bindgroup = || BindGroupNameProps::new(ANY, text(), text(), None);
+    let bindoption = || BindOption::new(ANY, text(), true, false);
+    vec![
+        // base
+        (L::Charset(Charset::ASCII), ValueKind::Charset),
+        (L::Name(text()), ValueKind::Text),
+        (L::Id(text()), ValueKind::Text),
+        (L::Type, ValueKind::Shell),
+        (L::Craftman(text()), ValueKind::Text),
+        (L::Craftmanw(text()), ValueKind::Text),
+        (L::Craftmanurl(text()), ValueKind::Text),
+        (L::Homeurl(text()), ValueKind::Text),
+        (L::Readme(text()), ValueKind::Text),
+        (L::ReadmeCharset(Charset::ASCII), ValueKind::Charset),
+        (L::MenuHidden, ValueKind::Hidden),
+        (L::SakuraName(text()), ValueKind::Text),
+        (L::SakuraName2(text()), ValueKind::Text),
+        (L::KeroName(text()), ValueKind::Text),
+        (L::CharName(ANY, text()), ValueKind::Text),
+        // shell representation
+        (L::SerikoZOrder(vec![]), ValueKind::ScopeIds),
+        (L::SerikoStickyWindow(vec![]), ValueKind::ScopeIds),
+        (
+            L::SerikoAlignmenttodesktop(SurfacePosition::Free),
+            ValueKind::SurfacePosition,
+        ),
+        (
+            L::SakuraSerikoAlignmenttodesktop(SurfacePosition::Free),
+            ValueKind::SurfacePosition,
+        ),
+        (
+            L::KeroSerikoAlignmenttodesktop(SurfacePosition::Free),
+            ValueKind::SurfacePosition,
+        ),
+        (
+            L::CharSerikoAlignmenttodesktop(ANY, SurfacePosition::Free),
+            ValueKind::SurfacePosition,
+        ),
+        (L::SakuraDefaultx(0), ValueKind::DesktopPosition),
+        (L::KeroDefaultx(0), ValueKind::DesktopPosition),
+        (L::CharDefaultx(ANY, 0), ValueKind::DesktopPosition),
+        (L::SakuraDefaulty(0), ValueKind::DesktopPosition),
+        (L::KeroDefaulty(0), ValueKind::DesktopPosition),
+        (L::CharDefaulty(ANY, 0), ValueKind::DesktopPosition),
+        (L::SakuraDefaultleft(0), ValueKind::DesktopPosition),
+        (L::KeroDefaultleft(0), ValueKind::DesktopPosition),
+        (L::CharDefaultleft(ANY, 0), ValueKind::DesktopPosition),
+        (L::SakuraDefaulttop(0), ValueKind::DesktopPosition),
+        (L::KeroDefaulttop(0), ValueKind::DesktopPosition),
+        (L::CharDefaulttop(ANY, 0), ValueKind::DesktopPosition),
+        // balloon representation
+        (L::SakuraBalloonOffsetx(0), ValueKind::DesktopPosition),
+        (L::SakuraBalloonOffsety(0), ValueKind::DesktopPosition),
+        (L::KeroBalloonOffsetx(0), ValueKind::DesktopPosition),
+        (L::KeroBalloonOffsety(0), ValueKind::DesktopPosition),
+        (
+            L::SakuraBalloonAlignment(BalloonPosition::None),
+            ValueKind::BalloonPosition,
+        ),
+        (
+            L::KeroBalloonAlignment(BalloonPosition::None),
+            ValueKind::BalloonPosition,
+        ),
+        (L::SakuraBalloonDontmove(0), ValueKind::BalloonDontmove),
+        (L::KeroBalloonDontmove(0), ValueKind::BalloonDontmove),
+        (L::CharBalloonDontmove(ANY, 0), ValueKind::BalloonDontmove),
+        // menu
+        (L::MenuFontName(text()), ValueKind::Text),
+        (L::MenuFontHeight(0), ValueKind::FontSize),
+        (L::MenuBackgroundBitmapFilename(text()), ValueKind::Text),
+        (L::MenuForegroundBitmapFilename(text()), ValueKind::Text),
+        (L::MenuSidebarBitmapFilename(text()), ValueKind::Text),
+        (L::MenuBackgroundFontColorR(0), ValueKind::Color),
+        (L::MenuBackgroundFontColorG(0), ValueKind::Color),
+        (L::MenuBackgroundFontColorB(0), ValueKind::Color),
+        (L::MenuForegroundFontColorR(0), ValueKind::Color),
+        (L::MenuForegroundFontColorG(0), ValueKind::Color),
+        (L::MenuForegroundFontColorB(0), ValueKind::Color),
+        (L::MenuSeparatorColorR(0), ValueKind::Color),
+        (L::MenuSeparatorColorG(0), ValueKind::Color),
+        (L::MenuSeparatorColorB(0), ValueKind::Color),
+        (L::MenuFrameColorR(0), ValueKind::Color),
+        (L::MenuFrameColorG(0), ValueKind::Color),
+        (L::MenuFrameColorB(0), ValueKind::Color),
+        (L::MenuDisableFontColorR(0), ValueKind::Color),
+        (L::MenuDisableFontColorG(0), ValueKind::Color),
+        (L::MenuDisableFontColorB(0), ValueKind::Color),
+        (
+            L::MenuBackgroundAlignment(MenuPositionForegroundBackgroundBase::Lefttop, None, None),
+            ValueKind::MenuAlignment,
+        ),
+        (
+            L::MenuForegroundAlignment(MenuPositionForegroundBackgroundBase::Lefttop, None, None),
+            ValueKind::MenuAlignment,
+        ),
+        (
+            L::MenuSidebarAlignment(MenuPositionSidebarBase::Top, None),
+            ValueKind::SidebarAlignment,
+        ),
+        // binding
+        (
+            L::SakuraBindgroupName(bindgroup()),
+            ValueKind::BindgroupName,
+        ),
+        (L::SakuraBindgroupDefault(ANY, 0), ValueKind::Flag),
+        (
+            L::SakuraBindgroupAddid(ANY, vec![]),
+            ValueKind::AnimationIds,
+        ),
+        (
+            L::SakuraBindoptionGroup(bindoption()),
+            ValueKind::BindOption,
+        ),
+        (
+            L::SakuraMenuitem(ANY, BindMenuItem::Line),
+            ValueKind::MenuItem,
+        ),
+        (
+            L::SakuraMenuitemex(ANY, text(), BindMenuItem::Line),
+            ValueKind::MenuItemex,
+        ),
+        (
+            L::SakuraMenu(BindMenuVisibility::Auto),
+            ValueKind::MenuVisibility,
+        ),
+        (L::KeroBindgroupName(bindgroup()), ValueKind::BindgroupName),
+        (L::KeroBindgroupDefault(ANY, 0), ValueKind::Flag),
+        (L::KeroBindgroupAddid(ANY, vec![]), ValueKind::AnimationIds),
+        (L::KeroBindoptionGroup(bindoption()), ValueKind::BindOption),
+        (
+            L::KeroMenuitem(ANY, BindMenuItem::Line),
+            ValueKind::MenuItem,
+        ),
+        (
+            L::KeroMenuitemex(ANY, text(), BindMenuItem::Line),
+            ValueKind::MenuItemex,
+        ),
+        (
+            L::KeroMenu(BindMenuVisibility::Auto),
+            ValueKind::MenuVisibility,
+        ),
+        (
+            L::CharBindgroupName(ANY, bindgroup()),
+            ValueKind::BindgroupName,
+        ),
+        (L::CharBindgroupDefault(ANY, ANY, 0), ValueKind::Flag),
+        (
+            L::CharBindgroupAddid(ANY, ANY, vec![]),
+            ValueKind::AnimationIds,
+        ),
+        (
+            L::CharBindoptionGroup(ANY, bindoption()),
+            ValueKind::BindOption,
+        ),
+        (
+            L::CharMenuitem(ANY, ANY, BindMenuItem::Line),
+            ValueKind::MenuItem,
+        ),
+        (
+            L::CharMenuitemex(ANY, ANY, text(), BindMenuItem::Line),
+            ValueKind::MenuItemex,
+        ),
+        (
+            L::CharMenu(ANY, BindMenuVisibility::Auto),
+            ValueKind::MenuVisibility,
+        ),
+        // alpha
+        (L::SerikoPaintTransparentRegionBlack(0), ValueKind::Flag),
+        (L::SerikoUseSelfAlpha(0), ValueKind::Flag),
+    ]
+}
+
+/// Known keys, where `*` stands for a number, made from the keys of [`samples`].
+fn keys() -> &'static [(String, ValueKind)] {
+    static KEYS: OnceLock<Vec<(String, ValueKind)>> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let any = ANY.to_string();
+        samples()
+            .into_iter()
+            .map(|(line, kind)| (line.key().replace(&any, "*"), kind))
+            .collect()
+    })
+}
+
+impl ValueKind {
+    /// Describes the value the key takes.
+    pub(crate) fn expected(&self) -> &'static str {
+        match self {
+            ValueKind::Charset => "charset name (ASCII, Shift_JIS, ISO-2022-JP, EUC-JP, UTF-8 or default)",
+            ValueKind::Text => "non-empty text",
+            ValueKind::Shell => "`shell`",
+            ValueKind::Hidden => "`hidden`",
+            ValueKind::ScopeIds => "comma-separated scope ids",
+            ValueKind::SurfacePosition => "`top`, `bottom` or `free`",
+            ValueKind::DesktopPosition => "integer",
+            ValueKind::BalloonPosition => "`none`, `left` or `right`",
+            ValueKind::BalloonDontmove => "integer 0-255 or `true`",
+            ValueKind::FontSize => "non-negative integer",
+            ValueKind::Color | ValueKind::Flag => "integer 0-255",
+            ValueKind::MenuAlignment => {
+                "`lefttop`, `centertop`, `righttop`, `leftbottom`, `centerbottom` or `rightbottom`, optionally followed by `+repeat-x` and `+repeat-y`"
+            }
+            ValueKind::SidebarAlignment => "`top` or `bottom`, optionally followed by `+repeat-y`",
+            ValueKind::BindgroupName => "`category,part` optionally followed by `,thumbnail`",
+            ValueKind::AnimationIds => "comma-separated animation ids",
+            ValueKind::BindOption => "`category,` followed by `mustselect` and/or `multiple` joined with `+`",
+            ValueKind::MenuItem => "animation id or `-`",
+            ValueKind::MenuItemex => "`menu name,` followed by animation id or `-`",
+            ValueKind::MenuVisibility => "`auto` or `hidden`",
+        }
+    }
+}
+
+/// Returns the kind of value `key` takes, if `key` is known.
+pub(crate) fn value_kind(key: &str) -> Option<ValueKind> {
+    keys()
+        .iter()
+        .find(|(pattern, _)| key_matches(pattern, key))
+        .map(|(_, v)| *v)
+}
+
+/// Returns the sample line of the known `key`, with the numbers in `key` standing for `*`.
+///
+/// Numbers of the sample line itself are all [`ANY`].
+pub(crate) fn key_sample(key: &str) -> Option<(ShellDescriptLine, Vec<u32>)> {
+    let index = keys()
+        .iter()
+        .position(|(pattern, _)| key_matches(pattern, key))?;
+    let numbers = key_numbers(&keys()[index].0, key);
+    Some((samples().swap_remove(index).0, numbers))
+}
+
+/// Numbers in `key` standing for `*` in `pattern`, which `key` matches.
+fn key_numbers(pattern: &str, key: &str) -> Vec<u32> {
+    let mut numbers = Vec::new();
+    let mut parts = pattern.split('*');
+    let mut rest = parts
+        .next()
+        .and_then(|v| key.strip_prefix(v))
+        .unwrap_or_default();
+    for part in parts {
+        let digits = rest.bytes().take_while(|v| v.is_ascii_digit()).count();
+        numbers.push(rest[..digits].parse().unwrap_or(u32::MAX));
+        rest = rest[digits..].strip_prefix(part).unwrap_or_default();
+    }
+    numbers
+}
+
+/// Checks whether `key` matches `pattern`, both separated by `.` into parts.
+///
+/// A part of `pattern` which is just `*` matches any one part, and `**` matches one or more
+/// parts. Otherwise `*` in a part stands for a number.
+pub(crate) fn key_matches(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let key: Vec<&str> = key.split('.').collect();
+    parts_match(&pattern, &key)
+}
+
+fn parts_match(pattern: &[&str], key: &[&str]) -> bool {
+    match (pattern.split_first(), key.split_first()) {
+        (None, None) => true,
+        (Some((&"**", rest)), _) => (1..=key.len()).any(|n| parts_match(rest, &key[n..])),
+        (Some((&"*", rest)), Some((_, key_rest))) => parts_match(rest, key_rest),
+        (Some((p, rest)), Some((k, key_rest))) => part_matches(p, k) && parts_match(rest, key_rest),
+        _ => false,
+    }
+}
+
+/// Checks whether a part of a key matches a part of a pattern, where `*` stands for a number.
+fn part_matches(pattern: &str, part: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == part,
+        Some((head, tail)) => match part.strip_prefix(head) {
+            Some(rest) => {
+                let digits = rest.bytes().take_while(|v| v.is_ascii_digit()).count();
+                digits > 0 && part_matches(tail, &rest[digits..])
+            }
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod value_kind {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            assert_eq!(value_kind("menu.frame.color.r"), Some(ValueKind::Color));
+            assert_eq!(
+                value_kind("char12.bindgroup3.name"),
+                Some(ValueKind::BindgroupName)
+            );
+            assert_eq!(value_kind("kero.menuitemex2"), Some(ValueKind::MenuItemex));
+            assert_eq!(value_kind("kero.menuitem2"), Some(ValueKind::MenuItem));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            assert_eq!(value_kind("somethingwrong"), None);
+            assert_eq!(value_kind("char.name"), None);
+            assert_eq!(value_kind("sakura.bindgroup.name"), None);
+        }
+    }
+
+    mod keys {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let result: Vec<&str> = keys().iter().map(|(v, _)| v.as_str()).collect();
+            assert_eq!(result.len(), samples().len());
+            assert!(result.contains(&"sakura.name2"));
+            assert!(result.contains(&"char*.bindgroup*.name"));
+            assert!(result.contains(&"kero.menuitemex*"));
+            for (i, v) in result.iter().enumerate() {
+                assert!(!result[i + 1..].contains(v), "{} is duplicated", v);
+            }
+        }
+    }
+
+    mod key_sample {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let (line, numbers) = key_sample("char12.bindgroup3.default").unwrap();
+            assert_eq!(line, ShellDescriptLine::CharBindgroupDefault(ANY, ANY, 0));
+            assert_eq!(numbers, vec![12, 3]);
+
+            let (line, numbers) = key_sample("sakura.name2").unwrap();
+            assert_eq!(line, ShellDescriptLine::SakuraName2(String::new()));
+            assert_eq!(numbers, vec![]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            assert_eq!(key_sample("somethingwrong"), None);
+        }
+    }
+
+    mod key_matches {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            assert!(key_matches(
+                "char*.bindgroup*.name",
+                "char2.bindgroup10.name"
+            ));
+            assert!(key_matches("sakura.menuitem*", "sakura.menuitem0"));
+            assert!(key_matches("type", "type"));
+            assert!(key_matches("*.name", "sakura.name"));
+            assert!(key_matches("menu.**.color.*", "menu.frame.color.r"));
+            assert!(key_matches(
+                "menu.**.color.*",
+                "menu.background.font.color.b"
+            ));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            assert!(!key_matches("sakura.menuitem*", "sakura.menuitemex0"));
+            assert!(!key_matches("char*.name", "charA.name"));
+            assert!(!key_matches("type", "types"));
+            assert!(!key_matches("*.name", "char2.bindgroup0.name"));
+            assert!(!key_matches("menu.**.color.*", "menu.color.r"));
+            assert!(!key_matches("menu.**", "menu"));
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use crate::ast::{LineContainer, LineSpan, ShellDescript, ShellDescriptLine};
+use crate::diagnostic::{Diagnostic, Severity};
+
+use super::error::ParseError;
+use super::keys::{value_kind, ValueKind};
+use super::shell_descript_line;
+
+/// Charset names as the parser accepts them.
+const CHARSETS: &[&str] = &[
+    "ASCII",
+    "Shift_JIS",
+    "ISO-2022-JP",
+    "EUC-JP",
+    "UTF-8",
+    "default",
+];
+
+/// Options of [`parse_with_options`].
+///
+/// [`parse_with_options`]: crate::parse::parse_with_options
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptions {
+    /// Rejects every non-empty line which is neither a known setting nor a `//` comment.
+    pub strict: bool,
+    /// Ignores whitespace around the key and around each comma-separated value.
+    /// A free-text value such as `name` is trimmed as a whole, keeping its commas as they are.
+    pub trim_whitespace: bool,
+    /// Reads keys, charset names and keywords such as `left` or `mustselect` regardless of case.
+    pub ignore_case: bool,
+}
+
+/// Normalisation applied to a line to read it.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Note {
+    code: &'static str,
+    message: String,
+    on_key: bool,
+}
+
+impl Note {
+    fn trimmed(on_key: bool) -> Note {
+        Note {
+            code: "trimmed-whitespace",
+            message: format!(
+                "whitespace around the {} is ignored",
+                if on_key { "key" } else { "value" }
+            ),
+            on_key,
+        }
+    }
+
+    fn cased(on_key: bool, from: &str, to: &str) -> Note {
+        Note {
+            code: "normalized-case",
+            message: format!(
+                "{} `{}` is read as `{}`",
+                if on_key { "key" } else { "value" },
+                from,
+                to
+            ),
+            on_key,
+        }
+    }
+
+    /// Builds the warning for the line at `index`.
+    pub(super) fn into_diagnostic(self, index: usize, span: Option<&LineSpan>) -> Diagnostic {
+        let span = span.map(|v| {
+            let part = if self.on_key { v.key() } else { v.value() };
+            part.unwrap_or(*v.line())
+        });
+        Diagnostic::new(
+            Severity::Warning,
+            self.code,
+            self.message,
+            Some(index),
+            span,
+        )
+    }
+}
+
+/// Reads `text` as a setting after the normalisations `options` allow, returning notes
+/// on each normalisation applied.
+pub(super) fn normalize(
+    text: &str,
+    options: &ParseOptions,
+) -> Option<(ShellDescriptLine, Vec<Note>)> {
+    if !options.trim_whitespace && !options.ignore_case {
+        return None;
+    }
+    let (raw_key, raw_value) = text.split_once(',')?;
+    let mut notes = Vec::new();
+
+    let mut key = raw_key.to_string();
+    if options.trim_whitespace && key.trim() != key {
+        key = key.trim().to_string();
+        notes.push(Note::trimmed(true));
+    }
+    if options.ignore_case && key.to_ascii_lowercase() != key {
+        let lower = key.to_ascii_lowercase();
+        notes.push(Note::cased(true, &key, &lower));
+        key = lower;
+    }
+    let kind = value_kind(&key)?;
+
+    // Free text may contain commas, which do not separate values there.
+    let mut values: Vec<String> = match kind {
+        ValueKind::Text => vec![raw_value.to_string()],
+        _ => raw_value.split(',').map(|v| v.to_string()).collect(),
+    };
+    if options.trim_whitespace && values.iter().any(|v| v.trim() != v) {
+        values = values.iter().map(|v| v.trim().to_string()).collect();
+        notes.push(Note::trimmed(false));
+    }
+    if options.ignore_case {
+        for (i, value) in values.iter_mut().enumerate() {
+            if let Some(normalized) = normalize_case(kind, i, value) {
+                if normalized != *value {
+                    notes.push(Note::cased(false, value, &normalized));
+                    *value = normalized;
+                }
+            }
+        }
+    }
+
+    let normalized = format!("{},{}", key, values.join(","));
+    match shell_descript_line(&normalized) {
+        Ok(("", line)) => Some((line, notes)),
+        _ => None,
+    }
+}
+
+/// Spelling of the `index`th comma-separated value which the parser accepts.
+fn normalize_case(kind: ValueKind, index: usize, value: &str) -> Option<String> {
+    match kind {
+        ValueKind::Charset => CHARSETS
+            .iter()
+            .find(|v| v.eq_ignore_ascii_case(value))
+            .map(|v| v.to_string()),
+        ValueKind::Shell
+        | ValueKind::Hidden
+        | ValueKind::SurfacePosition
+        | ValueKind::BalloonPosition
+        | ValueKind::BalloonDontmove
+        | ValueKind::MenuAlignment
+        | ValueKind::SidebarAlignment
+        | ValueKind::MenuVisibility => Some(value.to_ascii_lowercase()),
+        ValueKind::BindOption if index == 1 => Some(value.to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
+/// Errors for the lines strict parsing rejects.
+pub(super) fn strict_errors(descript: &ShellDescript) -> Vec<ParseError> {
+    descript
+        .lines()
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| match line {
+            LineContainer::CommentLine(v) => !v.starts_with("//"),
+            LineContainer::Invalid { .. } | LineContainer::Unknown { .. } => true,
+            LineContainer::EmptyLine | LineContainer::Body(_) => false,
+        })
+        .filter_map(|(i, _)| {
+            let start = *descript.span(i)?.line().start();
+            let text = descript.sources()[i].as_ref()?.text();
+            Some(ParseError::at_strict_line(start, text))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ast::Position;
+    use crate::parse::parse_lossless;
+
+    mod normalize {
+        use super::*;
+
+        use crate::ast::BalloonPosition;
+
+        #[test]
+        fn success_when_valid_str() {
+            let options = ParseOptions {
+                trim_whitespace: true,
+                ignore_case: true,
+                ..Default::default()
+            };
+            let (line, notes) = normalize(" Sakura.Balloon.Alignment , LEFT\t", &options).unwrap();
+            assert_eq!(
+                line,
+                ShellDescriptLine::SakuraBalloonAlignment(BalloonPosition::Left)
+            );
+            assert_eq!(
+                notes,
+                vec![
+                    Note::trimmed(true),
+                    Note::cased(true, "Sakura.Balloon.Alignment", "sakura.balloon.alignment"),
+                    Note::trimmed(false),
+                    Note::cased(false, "LEFT", "left"),
+                ]
+            );
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let options = ParseOptions {
+                trim_whitespace: true,
+                ..Default::default()
+            };
+            assert_eq!(normalize("Type,shell", &options), None);
+            assert_eq!(normalize("sakura.balloon.alignment,middle", &options), None);
+            assert_eq!(normalize("// comment, with comma", &options), None);
+            assert_eq!(normalize(" type,shell", &ParseOptions::default()), None);
+        }
+
+        #[test]
+        fn success_when_text_with_comma() {
+            let options = ParseOptions {
+                trim_whitespace: true,
+                ..Default::default()
+            };
+            let (line, notes) = normalize("craftman, ukadog , and friends ", &options).unwrap();
+            assert_eq!(
+                line,
+                ShellDescriptLine::Craftman("ukadog , and friends".to_string())
+            );
+            assert_eq!(notes, vec![Note::trimmed(false)]);
+        }
+    }
+
+    mod strict_errors {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\n\r\n// comment\r\nsakura.defaultx,10\r\n";
+            let result = strict_errors(&parse_lossless(case).unwrap());
+            assert_eq!(result, vec![]);
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,UTF-8\r\nnot comment\r\nmenu.font.height,big\r\nfoo.bar,1\r\n";
+            let result = strict_errors(&parse_lossless(case).unwrap());
+            assert_eq!(result.len(), 3);
+            assert_eq!(result[0].position(), &Position::new(15, 2, 1));
+            assert_eq!(result[0].text(), "not comment");
+            assert_eq!(result[1].position(), &Position::new(45, 3, 18));
+            assert_eq!(result[1].key(), &Some("menu.font.height".to_string()));
+            assert_eq!(result[2].position(), &Position::new(50, 4, 1));
+            assert_eq!(result[2].key(), &None);
+        }
+    }
+}
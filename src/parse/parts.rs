@@ -4,13 +4,15 @@ use nom::{
     branch::alt,
     bytes::complete::{is_not, tag},
     character::complete::digit1,
-    combinator::{eof, map, map_res, opt},
+    combinator::{consumed, eof, map, map_opt, map_res, opt},
     sequence::{preceded, terminated, tuple},
     IResult,
 };
 use shell_parser_common_rs::ShellParseError;
 
-use crate::ast::{CharacterIdType, LineContainer, ShellDescriptLine};
+use crate::ast::{CharacterIdType, LineContainer, LineSource, Newline, ShellDescriptLine};
+
+use super::keys::value_kind;
 
 pub(super) fn newline_body<'a>(input: &'a str) -> IResult<&'a str, &'a str, ShellParseError> {
     alt((tag("\r\n"), tag("\r"), tag("\n")))(input)
@@ -27,12 +29,68 @@ where
     })
 }
 
+pub(super) fn with_source<'a, F>(
+    f: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (LineContainer, LineSource), ShellParseError>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, LineContainer, ShellParseError>,
+{
+    map(consumed(f), |(raw, v)| (v, line_source(raw)))
+}
+
+fn line_source(raw: &str) -> LineSource {
+    let (text, newline) = if let Some(v) = raw.strip_suffix("\r\n") {
+        (v, Newline::CrLf)
+    } else if let Some(v) = raw.strip_suffix('\r') {
+        (v, Newline::Cr)
+    } else if let Some(v) = raw.strip_suffix('\n') {
+        (v, Newline::Lf)
+    } else {
+        (raw, Newline::Eof)
+    };
+    LineSource::new(text.to_string(), newline)
+}
+
 pub(super) fn empty_line<'a>(input: &'a str) -> IResult<&'a str, LineContainer, ShellParseError> {
     map(newline_body, |_| LineContainer::EmptyLine)(input)
 }
 
+pub(super) fn invalid_line<'a>(input: &'a str) -> IResult<&'a str, LineContainer, ShellParseError> {
+    map_opt(
+        terminated(is_not("\r\n"), alt((newline_body, eof))),
+        |v: &str| {
+            let (key, value) = v.split_once(',')?;
+            let kind = value_kind(key)?;
+            Some(LineContainer::Invalid {
+                key: key.to_string(),
+                value: value.to_string(),
+                reason: format!("expected {}", kind.expected()),
+            })
+        },
+    )(input)
+}
+
+pub(super) fn unknown_line<'a>(input: &'a str) -> IResult<&'a str, LineContainer, ShellParseError> {
+    map_opt(
+        terminated(is_not("\r\n"), alt((newline_body, eof))),
+        |v: &str| {
+            if v.starts_with("//") {
+                return None;
+            }
+            let (key, value) = v.split_once(',')?;
+            if key.is_empty() || key.contains(char::is_whitespace) {
+                return None;
+            }
+            Some(LineContainer::Unknown {
+                key: key.to_string(),
+                values: value.split(',').map(|v| v.to_string()).collect(),
+            })
+        },
+    )(input)
+}
+
 pub(super) fn comment_line<'a>(input: &'a str) -> IResult<&'a str, LineContainer, ShellParseError> {
-    map(terminated(is_not("\r\n"), newline_body), |v| {
+    map(terminated(is_not("\r\n"), alt((newline_body, eof))), |v| {
         LineContainer::CommentLine(v.to_string())
     })(input)
 }
@@ -130,6 +188,48 @@ mod tests {
         }
     }
 
+    mod with_source {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let mut case_func = with_source(comment_line);
+
+            let case = "aaa\rabc";
+            let (remain, (result, source)) = case_func(case).unwrap();
+            assert_eq!(remain, "abc");
+            assert_eq!(result, LineContainer::CommentLine("aaa".to_string()));
+            assert_eq!(source, LineSource::new("aaa".to_string(), Newline::Cr));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let mut case_func = with_source(comment_line);
+
+            let case = "\r\n";
+            assert!(case_func(case).is_err());
+        }
+    }
+
+    mod line_source {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "abc\r\n";
+            let result = line_source(case);
+            assert_eq!(result, LineSource::new("abc".to_string(), Newline::CrLf));
+
+            let case = "abc\n";
+            let result = line_source(case);
+            assert_eq!(result, LineSource::new("abc".to_string(), Newline::Lf));
+
+            let case = "abc";
+            let result = line_source(case);
+            assert_eq!(result, LineSource::new("abc".to_string(), Newline::Eof));
+        }
+    }
+
     mod empty_line {
         use super::*;
 
@@ -148,6 +248,74 @@ mod tests {
         }
     }
 
+    mod invalid_line {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "menu.frame.color.r,300\r\nabc";
+            let (remain, result) = invalid_line(case).unwrap();
+            assert_eq!(remain, "abc");
+            assert_eq!(
+                result,
+                LineContainer::Invalid {
+                    key: "menu.frame.color.r".to_string(),
+                    value: "300".to_string(),
+                    reason: "expected integer 0-255".to_string(),
+                }
+            );
+
+            let case = "menu.frame.color.r,300";
+            let (remain, result) = invalid_line(case).unwrap();
+            assert_eq!(remain, "");
+            assert!(matches!(result, LineContainer::Invalid { .. }));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "// menu.frame.color.r,300\r\n";
+            assert!(invalid_line(case).is_err());
+
+            let case = "somethingwrong,300\r\n";
+            assert!(invalid_line(case).is_err());
+        }
+    }
+
+    mod unknown_line {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "sakura.seriko.defaultsurface,0,,10\r\nabc";
+            let (remain, result) = unknown_line(case).unwrap();
+            assert_eq!(remain, "abc");
+            assert_eq!(
+                result,
+                LineContainer::Unknown {
+                    key: "sakura.seriko.defaultsurface".to_string(),
+                    values: vec!["0".to_string(), "".to_string(), "10".to_string()],
+                }
+            );
+
+            let case = "sakura.seriko.defaultsurface,0";
+            let (remain, result) = unknown_line(case).unwrap();
+            assert_eq!(remain, "");
+            assert!(matches!(result, LineContainer::Unknown { .. }));
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "//sakura.seriko.defaultsurface,0\r\n";
+            assert!(unknown_line(case).is_err());
+
+            let case = "this is, a comment\r\n";
+            assert!(unknown_line(case).is_err());
+
+            let case = "somethingwrong\r\n";
+            assert!(unknown_line(case).is_err());
+        }
+    }
+
     mod comment_line {
         use super::*;
 
@@ -157,12 +325,20 @@ mod tests {
             let (remain, result) = comment_line(case).unwrap();
             assert_eq!(remain, "abc");
             assert_eq!(result, LineContainer::CommentLine("aaa".to_string()));
+
+            let case = "// end";
+            let (remain, result) = comment_line(case).unwrap();
+            assert_eq!(remain, "");
+            assert_eq!(result, LineContainer::CommentLine("// end".to_string()));
         }
 
         #[test]
         fn failed_when_invalid_str() {
             let case = "\r\n";
             assert!(comment_line(case).is_err());
+
+            let case = "";
+            assert!(comment_line(case).is_err());
         }
     }
 
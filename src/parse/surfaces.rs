@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use nom::{
+    bytes::complete::tag,
+    combinator::rest,
+    sequence::{delimited, tuple},
+    IResult,
+};
+use shell_parser_common_rs::ShellParseError;
+
+use crate::ast::AnimationIdType;
+
+use super::decode_bytes;
+use super::parts::digit;
+
+/// Animation found in surfaces.txt by [`scan_animations`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationDefinition {
+    id: AnimationIdType,
+    intervals: Vec<String>,
+    surfaces: Vec<String>,
+}
+
+impl AnimationDefinition {
+    pub fn new(
+        id: AnimationIdType,
+        intervals: Vec<String>,
+        surfaces: Vec<String>,
+    ) -> AnimationDefinition {
+        AnimationDefinition {
+            id,
+            intervals,
+            surfaces,
+        }
+    }
+
+    pub fn id(&self) -> &AnimationIdType {
+        &self.id
+    }
+    /// Values of `animationN.interval`, one for each surface defining the animation.
+    pub fn intervals(&self) -> &Vec<String> {
+        &self.intervals
+    }
+    /// Headers of the blocks defining the animation, e.g. `surface0` or `surface.append10-19`,
+    /// in the same order as [`AnimationDefinition::intervals`].
+    pub fn surfaces(&self) -> &Vec<String> {
+        &self.surfaces
+    }
+    /// Whether the animation is a bind animation, i.e. its interval includes `bind`.
+    pub fn is_bind(&self) -> bool {
+        self.intervals
+            .iter()
+            .any(|v| v.split('+').any(|v| v.trim() == "bind"))
+    }
+}
+
+/// Scans surfaces.txt for `animationN.interval` lines, by `N`.
+///
+/// Only the animation ids, intervals and the headers of their blocks are read;
+/// the other lines are skipped.
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::scan_animations;
+///
+/// let case = r#"charset,UTF-8
+/// surface0
+/// {
+///     animation10.interval,bind
+///     animation10.pattern0,overlay,100,0,0,0
+///     animation20.interval,sometimes
+/// }
+/// "#;
+///
+/// let result = scan_animations(case);
+/// assert!(result.get(&10).unwrap().is_bind());
+/// assert_eq!(result.get(&10).unwrap().surfaces(), &vec!["surface0".to_string()]);
+/// assert!(!result.get(&20).unwrap().is_bind());
+/// ```
+pub fn scan_animations(input: &str) -> BTreeMap<AnimationIdType, AnimationDefinition> {
+    let mut result: BTreeMap<AnimationIdType, AnimationDefinition> = BTreeMap::new();
+    let mut header = String::new();
+    let mut depth = 0usize;
+    for line in input.split(['\r', '\n']) {
+        let line = line.trim();
+        if depth == 0 && !line.is_empty() && !line.starts_with("//") && !line.starts_with('{') {
+            header = line.trim_end_matches('{').trim_end().to_string();
+        }
+        if let Ok((_, (id, interval))) = animation_interval(line) {
+            let animation = result
+                .entry(id)
+                .or_insert_with(|| AnimationDefinition::new(id, Vec::new(), Vec::new()));
+            animation.intervals.push(interval.trim().to_string());
+            animation.surfaces.push(header.clone());
+        }
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+    }
+    result
+}
+
+/// Decodes surfaces.txt by its `charset` line with [`decode_bytes`], and scans it with
+/// [`scan_animations`].
+pub fn scan_animations_bytes(
+    input: &[u8],
+) -> Result<BTreeMap<AnimationIdType, AnimationDefinition>, String> {
+    decode_bytes(input).map(|v| scan_animations(&v))
+}
+
+fn animation_interval<'a>(
+    input: &'a str,
+) -> IResult<&'a str, (AnimationIdType, &'a str), ShellParseError> {
+    tuple((delimited(tag("animation"), digit, tag(".interval,")), rest))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod scan_animations {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,UTF-8\r\nsurface0{\r\n\tanimation10.interval,bind\r\n\tanimation11.interval,sometimes\r\n}\r\nsurface1,surface3\r\n{\r\nanimation10.interval,bind\r\nanimation11.interval,bind+runonce\r\n//animation12.interval,bind\r\nanimation13.pattern0,overlay,10,0,0,0\r\n}";
+            let result = scan_animations(case);
+            assert_eq!(
+                result.values().collect::<Vec<_>>(),
+                vec![
+                    &AnimationDefinition::new(
+                        10,
+                        vec!["bind".to_string(), "bind".to_string()],
+                        vec!["surface0".to_string(), "surface1,surface3".to_string()]
+                    ),
+                    &AnimationDefinition::new(
+                        11,
+                        vec!["sometimes".to_string(), "bind+runonce".to_string()],
+                        vec!["surface0".to_string(), "surface1,surface3".to_string()]
+                    ),
+                ]
+            );
+            assert!(result.get(&11).unwrap().is_bind());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case =
+                "animation.interval,bind\nanimationA.interval,bind\nanimation1.intervals,bind\n";
+            assert!(scan_animations(case).is_empty());
+        }
+    }
+
+    mod scan_animations_bytes {
+        use super::*;
+
+        use encoding_rs::SHIFT_JIS;
+
+        #[test]
+        fn success_when_valid_str() {
+            let (case, _, _) =
+                SHIFT_JIS.encode("charset,Shift_JIS\r\n// 帽子\r\nanimation3.interval,bind\r\n");
+            let result = scan_animations_bytes(&case).unwrap();
+            assert_eq!(result.keys().collect::<Vec<_>>(), vec![&3]);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Looks up lines of a [`ShellDescript`] by key.
+//!
+//! Keys are written as in `descript.txt`, e.g. `sakura.balloon.offsetx`.
+//! `*` in a key stands for any number, so `char*.bindgroup*.name` matches
+//! `char2.bindgroup10.name`. A part between dots which is just `*` stands for any one part,
+//! and `**` for one or more parts, so `menu.**.color.*` matches every menu colour.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use crate::ast::{LineContainer, ShellDescript, ShellDescriptLine};
+use crate::parse::keys::key_matches;
+
+impl ShellDescript {
+    /// Returns the first line whose key matches `key`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::{parse, ShellDescriptLine};
+    ///
+    /// let case = r#"charset,UTF-8
+    /// sakura.balloon.offsetx,10
+    /// sakura.balloon.offsetx,20
+    /// "#;
+    ///
+    /// let descript = parse(case).unwrap();
+    /// assert_eq!(
+    ///     descript.get("sakura.balloon.offsetx"),
+    ///     Some(&ShellDescriptLine::SakuraBalloonOffsetx(10))
+    /// );
+    /// assert_eq!(
+    ///     descript.get_last("sakura.balloon.offsetx"),
+    ///     Some(&ShellDescriptLine::SakuraBalloonOffsetx(20))
+    /// );
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&ShellDescriptLine> {
+        self.bodies().find(|v| key_matches(key, &v.key()))
+    }
+
+    /// Returns the last line whose key matches `key`.
+    pub fn get_last(&self, key: &str) -> Option<&ShellDescriptLine> {
+        self.bodies().rev().find(|v| key_matches(key, &v.key()))
+    }
+
+    /// Returns all the lines whose key matches `key`, in order of appearance.
+    pub fn get_all(&self, key: &str) -> Vec<&ShellDescriptLine> {
+        self.bodies()
+            .filter(|v| key_matches(key, &v.key()))
+            .collect()
+    }
+
+    fn bodies(&self) -> impl DoubleEndedIterator<Item = &ShellDescriptLine> {
+        self.lines().iter().filter_map(|v| match v {
+            LineContainer::Body(v) => Some(v),
+            _ => None,
+        })
+    }
+}
+
+/// Returns the first line of `descript` whose key matches `key`.
+///
+/// Same as [`ShellDescript::get`].
+pub fn get<'a>(descript: &'a ShellDescript, key: &str) -> Option<&'a ShellDescriptLine> {
+    descript.get(key)
+}
+
+/// Returns the last line of `descript` whose key matches `key`.
+///
+/// Same as [`ShellDescript::get_last`].
+pub fn get_last<'a>(descript: &'a ShellDescript, key: &str) -> Option<&'a ShellDescriptLine> {
+    descript.get_last(key)
+}
+
+/// Returns all the lines of `descript` whose key matches `key`, in order of appearance.
+///
+/// Same as [`ShellDescript::get_all`].
+pub fn get_all<'a>(descript: &'a ShellDescript, key: &str) -> Vec<&'a ShellDescriptLine> {
+    descript.get_all(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ast::BindMenuItem;
+    use crate::parse::parse;
+
+    const CASE: &str = r#"charset,UTF-8
+name,master
+// name,comment
+sakura.menuitem0,2
+sakura.menuitemex1,帽子,3
+sakura.menuitem1,-
+char2.bindgroup0.name,帽子,麦わら
+char3.bindgroup10.name,服,エプロン
+"#;
+
+    mod get {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let descript = parse(CASE).unwrap();
+            assert_eq!(
+                descript.get("name"),
+                Some(&ShellDescriptLine::Name("master".to_string()))
+            );
+            assert_eq!(
+                descript.get("sakura.menuitem*"),
+                Some(&ShellDescriptLine::SakuraMenuitem(0, BindMenuItem::Id(2)))
+            );
+            assert_eq!(
+                descript.get("sakura.menuitem1"),
+                Some(&ShellDescriptLine::SakuraMenuitem(1, BindMenuItem::Line))
+            );
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let descript = parse(CASE).unwrap();
+            assert_eq!(descript.get("homeurl"), None);
+            assert_eq!(descript.get("sakura.menuitem2"), None);
+            assert_eq!(descript.get("char*.name"), None);
+        }
+    }
+
+    mod get_last {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let descript = parse(CASE).unwrap();
+            assert_eq!(
+                descript.get_last("sakura.menuitem*"),
+                Some(&ShellDescriptLine::SakuraMenuitem(1, BindMenuItem::Line))
+            );
+        }
+    }
+
+    mod get_all {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let descript = parse(CASE).unwrap();
+            let result = descript.get_all("char*.bindgroup*.name");
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].key(), "char2.bindgroup0.name");
+            assert_eq!(result[1].key(), "char3.bindgroup10.name");
+            assert!(descript.get_all("kero.menuitem*").is_empty());
+        }
+    }
+}
@@ -0,0 +1,866 @@
+//! Resolves a [`ShellDescript`] into typed settings.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    AnimationIdType, BalloonPosition, BindGroupNameProps, BindMenuItem, BindMenuVisibility,
+    BindOption, CharacterIdType, ColorType, DesktopPositionType, FlagType, FontSizeType,
+    LineContainer, MenuPositionForegroundBackgroundBase, MenuPositionForegroundBackgroundRepeat,
+    MenuPositionSidebarBase, MenuPositionSidebarRepeat, ShellDescript, ShellDescriptLine,
+    SurfacePosition,
+};
+
+/// Base and repeat options of `menu.background.alignment` and `menu.foreground.alignment`.
+pub type MenuAlignment = (
+    MenuPositionForegroundBackgroundBase,
+    Option<MenuPositionForegroundBackgroundRepeat>,
+    Option<MenuPositionForegroundBackgroundRepeat>,
+);
+
+/// Settings resolved from a [`ShellDescript`].
+///
+/// When a key is given more than once, the last one wins as the baseware does.
+///
+/// [`ShellDescript`]: crate::ast::ShellDescript
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShellConfig {
+    charset: Option<Charset>,
+    name: Option<String>,
+    id: Option<String>,
+    is_shell: bool,
+    craftman: Option<String>,
+    craftmanw: Option<String>,
+    craftmanurl: Option<String>,
+    homeurl: Option<String>,
+    readme: Option<String>,
+    readme_charset: Option<Charset>,
+    is_menu_hidden: bool,
+    seriko: SerikoConfig,
+    menu: MenuConfig,
+    characters: BTreeMap<CharacterIdType, CharacterConfig>,
+}
+
+/// Settings given with `seriko.`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SerikoConfig {
+    zorder: Option<Vec<CharacterIdType>>,
+    sticky_window: Option<Vec<CharacterIdType>>,
+    alignmenttodesktop: Option<SurfacePosition>,
+    paint_transparent_region_black: Option<FlagType>,
+    use_self_alpha: Option<FlagType>,
+}
+
+/// Settings given with `menu.`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MenuConfig {
+    font_name: Option<String>,
+    font_height: Option<FontSizeType>,
+    background_bitmap_filename: Option<String>,
+    foreground_bitmap_filename: Option<String>,
+    sidebar_bitmap_filename: Option<String>,
+    background_font_color: ColorConfig,
+    foreground_font_color: ColorConfig,
+    separator_color: ColorConfig,
+    frame_color: ColorConfig,
+    disable_font_color: ColorConfig,
+    background_alignment: Option<MenuAlignment>,
+    foreground_alignment: Option<MenuAlignment>,
+    sidebar_alignment: Option<(MenuPositionSidebarBase, Option<MenuPositionSidebarRepeat>)>,
+}
+
+/// Color given with `.r`, `.g` and `.b`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColorConfig {
+    r: Option<ColorType>,
+    g: Option<ColorType>,
+    b: Option<ColorType>,
+}
+
+/// Settings of a character.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CharacterConfig {
+    name: Option<String>,
+    name2: Option<String>,
+    seriko_alignmenttodesktop: Option<SurfacePosition>,
+    defaultx: Option<DesktopPositionType>,
+    defaulty: Option<DesktopPositionType>,
+    defaultleft: Option<DesktopPositionType>,
+    defaulttop: Option<DesktopPositionType>,
+    balloon_offsetx: Option<DesktopPositionType>,
+    balloon_offsety: Option<DesktopPositionType>,
+    balloon_alignment: Option<BalloonPosition>,
+    balloon_dontmove: Option<FlagType>,
+    bindgroups: BTreeMap<AnimationIdType, BindGroupNameProps>,
+    bindgroup_defaults: BTreeMap<AnimationIdType, FlagType>,
+    bindgroup_addids: BTreeMap<AnimationIdType, Vec<AnimationIdType>>,
+    bindoptions: BTreeMap<u32, BindOption>,
+    menuitems: BTreeMap<u32, BindMenuItem>,
+    menuitemexes: BTreeMap<u32, (String, BindMenuItem)>,
+    menu: Option<BindMenuVisibility>,
+}
+
+/// Settings of a character merged over its scope.
+///
+/// Scope 0 is `sakura`, scope 1 is `kero` and scope N is `charN`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CharacterSettings {
+    scope: CharacterIdType,
+    alignmenttodesktop: Option<SurfacePosition>,
+    config: CharacterConfig,
+}
+
+impl ShellDescript {
+    /// Resolves the lines into [`ShellConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::parse;
+    ///
+    /// let case = r#"charset,UTF-8
+    /// name,master
+    /// sakura.defaultx,10
+    /// sakura.defaultx,-20
+    /// char2.name,third
+    /// "#;
+    ///
+    /// let config = parse(case).unwrap().resolve();
+    /// assert_eq!(config.name(), &Some("master".to_string()));
+    /// assert_eq!(config.sakura().defaultx(), &Some(-20));
+    /// assert_eq!(
+    ///     config.chars().get(&2).and_then(|v| v.name().clone()),
+    ///     Some("third".to_string())
+    /// );
+    /// ```
+    pub fn resolve(&self) -> ShellConfig {
+        let mut config = ShellConfig::default();
+        for line in self.lines().iter() {
+            if let LineContainer::Body(v) = line {
+                config.apply(v);
+            }
+        }
+        config
+    }
+
+    /// Resolves the settings of the character at `scope`.
+    ///
+    /// See [`ShellConfig::character`] for the precedence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shell_parser_descript_rs::parse;
+    ///
+    /// let case = r#"charset,UTF-8
+    /// kero.name,unyu
+    /// char1.defaultx,100
+    /// "#;
+    ///
+    /// let kero = parse(case).unwrap().character(1);
+    /// assert_eq!(kero.name(), &Some("unyu".to_string()));
+    /// assert_eq!(kero.default_position(), (Some(100), None));
+    /// ```
+    pub fn character(&self, scope: CharacterIdType) -> CharacterSettings {
+        self.resolve().character(scope)
+    }
+}
+
+impl ShellConfig {
+    pub fn charset(&self) -> &Option<Charset> {
+        &self.charset
+    }
+    pub fn name(&self) -> &Option<String> {
+        &self.name
+    }
+    pub fn id(&self) -> &Option<String> {
+        &self.id
+    }
+    /// Whether `type,shell` is given.
+    pub fn is_shell(&self) -> &bool {
+        &self.is_shell
+    }
+    pub fn craftman(&self) -> &Option<String> {
+        &self.craftman
+    }
+    pub fn craftmanw(&self) -> &Option<String> {
+        &self.craftmanw
+    }
+    pub fn craftmanurl(&self) -> &Option<String> {
+        &self.craftmanurl
+    }
+    pub fn homeurl(&self) -> &Option<String> {
+        &self.homeurl
+    }
+    pub fn readme(&self) -> &Option<String> {
+        &self.readme
+    }
+    pub fn readme_charset(&self) -> &Option<Charset> {
+        &self.readme_charset
+    }
+    /// Whether `menu,hidden` is given.
+    pub fn is_menu_hidden(&self) -> &bool {
+        &self.is_menu_hidden
+    }
+    pub fn seriko(&self) -> &SerikoConfig {
+        &self.seriko
+    }
+    pub fn menu(&self) -> &MenuConfig {
+        &self.menu
+    }
+    /// Settings given with `sakura.` or `char0.`.
+    pub fn sakura(&self) -> &CharacterConfig {
+        self.chars().get(&0).unwrap_or(empty())
+    }
+    /// Settings given with `kero.` or `char1.`.
+    pub fn kero(&self) -> &CharacterConfig {
+        self.chars().get(&1).unwrap_or(empty())
+    }
+    /// Settings of the characters given any, by scope.
+    ///
+    /// Scope 0 includes the settings given with `sakura.`, and scope 1 with `kero.`.
+    pub fn chars(&self) -> &BTreeMap<CharacterIdType, CharacterConfig> {
+        &self.characters
+    }
+
+    /// Scopes of the characters given any settings, always including 0 and 1.
+    pub fn scopes(&self) -> Vec<CharacterIdType> {
+        let mut scopes = vec![0, 1];
+        scopes.extend(self.characters.keys().filter(|v| **v > 1));
+        scopes
+    }
+
+    /// Settings of the character at `scope`.
+    ///
+    /// For scope 0 and 1, settings given with `sakura.` and `char0.`, or with `kero.`
+    /// and `char1.`, are the same settings, and the last one given wins.
+    /// `seriko.alignmenttodesktop` applies when the character has no own one.
+    pub fn character(&self, scope: CharacterIdType) -> CharacterSettings {
+        let config = self.characters.get(&scope).cloned().unwrap_or_default();
+        CharacterSettings {
+            scope,
+            alignmenttodesktop: config
+                .seriko_alignmenttodesktop
+                .clone()
+                .or_else(|| self.seriko.alignmenttodesktop.clone()),
+            config,
+        }
+    }
+
+    fn apply(&mut self, line: &ShellDescriptLine) {
+        if let Some(prefix) = Prefix::of(line) {
+            self.characters
+                .entry(prefix.scope())
+                .or_default()
+                .apply(line);
+            return;
+        }
+
+        match line {
+            // base
+            ShellDescriptLine::Charset(v) => self.charset = Some(v.clone()),
+            ShellDescriptLine::Name(v) => self.name = Some(v.clone()),
+            ShellDescriptLine::Id(v) => self.id = Some(v.clone()),
+            ShellDescriptLine::Type => self.is_shell = true,
+            ShellDescriptLine::Craftman(v) => self.craftman = Some(v.clone()),
+            ShellDescriptLine::Craftmanw(v) => self.craftmanw = Some(v.clone()),
+            ShellDescriptLine::Craftmanurl(v) => self.craftmanurl = Some(v.clone()),
+            ShellDescriptLine::Homeurl(v) => self.homeurl = Some(v.clone()),
+            ShellDescriptLine::Readme(v) => self.readme = Some(v.clone()),
+            ShellDescriptLine::ReadmeCharset(v) => self.readme_charset = Some(v.clone()),
+            ShellDescriptLine::MenuHidden => self.is_menu_hidden = true,
+            // shell representation
+            ShellDescriptLine::SerikoZOrder(v) => self.seriko.zorder = Some(v.clone()),
+            ShellDescriptLine::SerikoStickyWindow(v) => self.seriko.sticky_window = Some(v.clone()),
+            ShellDescriptLine::SerikoAlignmenttodesktop(v) => {
+                self.seriko.alignmenttodesktop = Some(v.clone())
+            }
+            // menu
+            ShellDescriptLine::MenuFontName(v) => self.menu.font_name = Some(v.clone()),
+            ShellDescriptLine::MenuFontHeight(v) => self.menu.font_height = Some(*v),
+            ShellDescriptLine::MenuBackgroundBitmapFilename(v) => {
+                self.menu.background_bitmap_filename = Some(v.clone())
+            }
+            ShellDescriptLine::MenuForegroundBitmapFilename(v) => {
+                self.menu.foreground_bitmap_filename = Some(v.clone())
+            }
+            ShellDescriptLine::MenuSidebarBitmapFilename(v) => {
+                self.menu.sidebar_bitmap_filename = Some(v.clone())
+            }
+            ShellDescriptLine::MenuBackgroundFontColorR(v) => {
+                self.menu.background_font_color.r = Some(*v)
+            }
+            ShellDescriptLine::MenuBackgroundFontColorG(v) => {
+                self.menu.background_font_color.g = Some(*v)
+            }
+            ShellDescriptLine::MenuBackgroundFontColorB(v) => {
+                self.menu.background_font_color.b = Some(*v)
+            }
+            ShellDescriptLine::MenuForegroundFontColorR(v) => {
+                self.menu.foreground_font_color.r = Some(*v)
+            }
+            ShellDescriptLine::MenuForegroundFontColorG(v) => {
+                self.menu.foreground_font_color.g = Some(*v)
+            }
+            ShellDescriptLine::MenuForegroundFontColorB(v) => {
+                self.menu.foreground_font_color.b = Some(*v)
+            }
+            ShellDescriptLine::MenuSeparatorColorR(v) => self.menu.separator_color.r = Some(*v),
+            ShellDescriptLine::MenuSeparatorColorG(v) => self.menu.separator_color.g = Some(*v),
+            ShellDescriptLine::MenuSeparatorColorB(v) => self.menu.separator_color.b = Some(*v),
+            ShellDescriptLine::MenuFrameColorR(v) => self.menu.frame_color.r = Some(*v),
+            ShellDescriptLine::MenuFrameColorG(v) => self.menu.frame_color.g = Some(*v),
+            ShellDescriptLine::MenuFrameColorB(v) => self.menu.frame_color.b = Some(*v),
+            ShellDescriptLine::MenuDisableFontColorR(v) => {
+                self.menu.disable_font_color.r = Some(*v)
+            }
+            ShellDescriptLine::MenuDisableFontColorG(v) => {
+                self.menu.disable_font_color.g = Some(*v)
+            }
+            ShellDescriptLine::MenuDisableFontColorB(v) => {
+                self.menu.disable_font_color.b = Some(*v)
+            }
+            ShellDescriptLine::MenuBackgroundAlignment(b, r1, r2) => {
+                self.menu.background_alignment = Some((b.clone(), r1.clone(), r2.clone()))
+            }
+            ShellDescriptLine::MenuForegroundAlignment(b, r1, r2) => {
+                self.menu.foreground_alignment = Some((b.clone(), r1.clone(), r2.clone()))
+            }
+            ShellDescriptLine::MenuSidebarAlignment(b, r) => {
+                self.menu.sidebar_alignment = Some((b.clone(), r.clone()))
+            }
+            // alpha
+            ShellDescriptLine::SerikoPaintTransparentRegionBlack(v) => {
+                self.seriko.paint_transparent_region_black = Some(*v)
+            }
+            ShellDescriptLine::SerikoUseSelfAlpha(v) => self.seriko.use_self_alpha = Some(*v),
+            _ => {}
+        }
+    }
+}
+
+/// Settings of a character given nothing.
+fn empty() -> &'static CharacterConfig {
+    static EMPTY: OnceLock<CharacterConfig> = OnceLock::new();
+    EMPTY.get_or_init(CharacterConfig::default)
+}
+
+/// Prefix of a line setting a character.
+#[derive(Debug, Clone, Copy)]
+enum Prefix {
+    Sakura,
+    Kero,
+    Char(CharacterIdType),
+}
+
+impl Prefix {
+    fn of(line: &ShellDescriptLine) -> Option<Prefix> {
+        Some(match line {
+            ShellDescriptLine::SakuraName(_)
+            | ShellDescriptLine::SakuraName2(_)
+            | ShellDescriptLine::SakuraSerikoAlignmenttodesktop(_)
+            | ShellDescriptLine::SakuraDefaultx(_)
+            | ShellDescriptLine::SakuraDefaulty(_)
+            | ShellDescriptLine::SakuraDefaultleft(_)
+            | ShellDescriptLine::SakuraDefaulttop(_)
+            | ShellDescriptLine::SakuraBalloonOffsetx(_)
+            | ShellDescriptLine::SakuraBalloonOffsety(_)
+            | ShellDescriptLine::SakuraBalloonAlignment(_)
+            | ShellDescriptLine::SakuraBalloonDontmove(_)
+            | ShellDescriptLine::SakuraBindgroupName(_)
+            | ShellDescriptLine::SakuraBindgroupDefault(_, _)
+            | ShellDescriptLine::SakuraBindgroupAddid(_, _)
+            | ShellDescriptLine::SakuraBindoptionGroup(_)
+            | ShellDescriptLine::SakuraMenuitem(_, _)
+            | ShellDescriptLine::SakuraMenuitemex(_, _, _)
+            | ShellDescriptLine::SakuraMenu(_) => Prefix::Sakura,
+            ShellDescriptLine::KeroName(_)
+            | ShellDescriptLine::KeroSerikoAlignmenttodesktop(_)
+            | ShellDescriptLine::KeroDefaultx(_)
+            | ShellDescriptLine::KeroDefaulty(_)
+            | ShellDescriptLine::KeroDefaultleft(_)
+            | ShellDescriptLine::KeroDefaulttop(_)
+            | ShellDescriptLine::KeroBalloonOffsetx(_)
+            | ShellDescriptLine::KeroBalloonOffsety(_)
+            | ShellDescriptLine::KeroBalloonAlignment(_)
+            | ShellDescriptLine::KeroBalloonDontmove(_)
+            | ShellDescriptLine::KeroBindgroupName(_)
+            | ShellDescriptLine::KeroBindgroupDefault(_, _)
+            | ShellDescriptLine::KeroBindgroupAddid(_, _)
+            | ShellDescriptLine::KeroBindoptionGroup(_)
+            | ShellDescriptLine::KeroMenuitem(_, _)
+            | ShellDescriptLine::KeroMenuitemex(_, _, _)
+            | ShellDescriptLine::KeroMenu(_) => Prefix::Kero,
+            ShellDescriptLine::CharName(c, _)
+            | ShellDescriptLine::CharSerikoAlignmenttodesktop(c, _)
+            | ShellDescriptLine::CharDefaultx(c, _)
+            | ShellDescriptLine::CharDefaulty(c, _)
+            | ShellDescriptLine::CharDefaultleft(c, _)
+            | ShellDescriptLine::CharDefaulttop(c, _)
+            | ShellDescriptLine::CharBalloonDontmove(c, _)
+            | ShellDescriptLine::CharBindgroupName(c, _)
+            | ShellDescriptLine::CharBindgroupDefault(c, _, _)
+            | ShellDescriptLine::CharBindgroupAddid(c, _, _)
+            | ShellDescriptLine::CharBindoptionGroup(c, _)
+            | ShellDescriptLine::CharMenuitem(c, _, _)
+            | ShellDescriptLine::CharMenuitemex(c, _, _, _)
+            | ShellDescriptLine::CharMenu(c, _) => Prefix::Char(*c),
+            _ => return None,
+        })
+    }
+
+    /// Scope of the character, where `sakura.` is 0 and `kero.` is 1.
+    fn scope(&self) -> CharacterIdType {
+        match self {
+            Prefix::Sakura => 0,
+            Prefix::Kero => 1,
+            Prefix::Char(c) => *c,
+        }
+    }
+}
+
+impl SerikoConfig {
+    pub fn zorder(&self) -> &Option<Vec<CharacterIdType>> {
+        &self.zorder
+    }
+    pub fn sticky_window(&self) -> &Option<Vec<CharacterIdType>> {
+        &self.sticky_window
+    }
+    pub fn alignmenttodesktop(&self) -> &Option<SurfacePosition> {
+        &self.alignmenttodesktop
+    }
+    pub fn paint_transparent_region_black(&self) -> &Option<FlagType> {
+        &self.paint_transparent_region_black
+    }
+    pub fn use_self_alpha(&self) -> &Option<FlagType> {
+        &self.use_self_alpha
+    }
+}
+
+impl MenuConfig {
+    pub fn font_name(&self) -> &Option<String> {
+        &self.font_name
+    }
+    pub fn font_height(&self) -> &Option<FontSizeType> {
+        &self.font_height
+    }
+    pub fn background_bitmap_filename(&self) -> &Option<String> {
+        &self.background_bitmap_filename
+    }
+    pub fn foreground_bitmap_filename(&self) -> &Option<String> {
+        &self.foreground_bitmap_filename
+    }
+    pub fn sidebar_bitmap_filename(&self) -> &Option<String> {
+        &self.sidebar_bitmap_filename
+    }
+    pub fn background_font_color(&self) -> &ColorConfig {
+        &self.background_font_color
+    }
+    pub fn foreground_font_color(&self) -> &ColorConfig {
+        &self.foreground_font_color
+    }
+    pub fn separator_color(&self) -> &ColorConfig {
+        &self.separator_color
+    }
+    pub fn frame_color(&self) -> &ColorConfig {
+        &self.frame_color
+    }
+    pub fn disable_font_color(&self) -> &ColorConfig {
+        &self.disable_font_color
+    }
+    pub fn background_alignment(&self) -> &Option<MenuAlignment> {
+        &self.background_alignment
+    }
+    pub fn foreground_alignment(&self) -> &Option<MenuAlignment> {
+        &self.foreground_alignment
+    }
+    pub fn sidebar_alignment(
+        &self,
+    ) -> &Option<(MenuPositionSidebarBase, Option<MenuPositionSidebarRepeat>)> {
+        &self.sidebar_alignment
+    }
+}
+
+impl ColorConfig {
+    pub fn r(&self) -> &Option<ColorType> {
+        &self.r
+    }
+    pub fn g(&self) -> &Option<ColorType> {
+        &self.g
+    }
+    pub fn b(&self) -> &Option<ColorType> {
+        &self.b
+    }
+
+    /// Returns the color when all of `r`, `g` and `b` are given.
+    pub fn rgb(&self) -> Option<(ColorType, ColorType, ColorType)> {
+        Some((self.r?, self.g?, self.b?))
+    }
+}
+
+impl CharacterConfig {
+    pub fn name(&self) -> &Option<String> {
+        &self.name
+    }
+    /// Only given for sakura.
+    pub fn name2(&self) -> &Option<String> {
+        &self.name2
+    }
+    pub fn seriko_alignmenttodesktop(&self) -> &Option<SurfacePosition> {
+        &self.seriko_alignmenttodesktop
+    }
+    pub fn defaultx(&self) -> &Option<DesktopPositionType> {
+        &self.defaultx
+    }
+    pub fn defaulty(&self) -> &Option<DesktopPositionType> {
+        &self.defaulty
+    }
+    pub fn defaultleft(&self) -> &Option<DesktopPositionType> {
+        &self.defaultleft
+    }
+    pub fn defaulttop(&self) -> &Option<DesktopPositionType> {
+        &self.defaulttop
+    }
+    /// Only given for sakura and kero.
+    pub fn balloon_offsetx(&self) -> &Option<DesktopPositionType> {
+        &self.balloon_offsetx
+    }
+    /// Only given for sakura and kero.
+    pub fn balloon_offsety(&self) -> &Option<DesktopPositionType> {
+        &self.balloon_offsety
+    }
+    /// Only given for sakura and kero.
+    pub fn balloon_alignment(&self) -> &Option<BalloonPosition> {
+        &self.balloon_alignment
+    }
+    pub fn balloon_dontmove(&self) -> &Option<FlagType> {
+        &self.balloon_dontmove
+    }
+    /// `bindgroupN.name` by `N`.
+    pub fn bindgroups(&self) -> &BTreeMap<AnimationIdType, BindGroupNameProps> {
+        &self.bindgroups
+    }
+    /// `bindgroupN.default` by `N`.
+    pub fn bindgroup_defaults(&self) -> &BTreeMap<AnimationIdType, FlagType> {
+        &self.bindgroup_defaults
+    }
+    /// `bindgroupN.addid` by `N`.
+    pub fn bindgroup_addids(&self) -> &BTreeMap<AnimationIdType, Vec<AnimationIdType>> {
+        &self.bindgroup_addids
+    }
+    /// `bindoptionN.group` by `N`.
+    pub fn bindoptions(&self) -> &BTreeMap<u32, BindOption> {
+        &self.bindoptions
+    }
+    /// `menuitemN` by `N`.
+    pub fn menuitems(&self) -> &BTreeMap<u32, BindMenuItem> {
+        &self.menuitems
+    }
+    /// `menuitemexN` by `N`.
+    pub fn menuitemexes(&self) -> &BTreeMap<u32, (String, BindMenuItem)> {
+        &self.menuitemexes
+    }
+    pub fn menu(&self) -> &Option<BindMenuVisibility> {
+        &self.menu
+    }
+
+    /// Applies a line setting a character, whichever prefix it has.
+    fn apply(&mut self, line: &ShellDescriptLine) {
+        match line {
+            // base
+            ShellDescriptLine::SakuraName(v)
+            | ShellDescriptLine::KeroName(v)
+            | ShellDescriptLine::CharName(_, v) => self.name = Some(v.clone()),
+            ShellDescriptLine::SakuraName2(v) => self.name2 = Some(v.clone()),
+            // shell representation
+            ShellDescriptLine::SakuraSerikoAlignmenttodesktop(v)
+            | ShellDescriptLine::KeroSerikoAlignmenttodesktop(v)
+            | ShellDescriptLine::CharSerikoAlignmenttodesktop(_, v) => {
+                self.seriko_alignmenttodesktop = Some(v.clone())
+            }
+            ShellDescriptLine::SakuraDefaultx(v)
+            | ShellDescriptLine::KeroDefaultx(v)
+            | ShellDescriptLine::CharDefaultx(_, v) => self.defaultx = Some(*v),
+            ShellDescriptLine::SakuraDefaulty(v)
+            | ShellDescriptLine::KeroDefaulty(v)
+            | ShellDescriptLine::CharDefaulty(_, v) => self.defaulty = Some(*v),
+            ShellDescriptLine::SakuraDefaultleft(v)
+            | ShellDescriptLine::KeroDefaultleft(v)
+            | ShellDescriptLine::CharDefaultleft(_, v) => self.defaultleft = Some(*v),
+            ShellDescriptLine::SakuraDefaulttop(v)
+            | ShellDescriptLine::KeroDefaulttop(v)
+            | ShellDescriptLine::CharDefaulttop(_, v) => self.defaulttop = Some(*v),
+            // balloon representation
+            ShellDescriptLine::SakuraBalloonOffsetx(v)
+            | ShellDescriptLine::KeroBalloonOffsetx(v) => self.balloon_offsetx = Some(*v),
+            ShellDescriptLine::SakuraBalloonOffsety(v)
+            | ShellDescriptLine::KeroBalloonOffsety(v) => self.balloon_offsety = Some(*v),
+            ShellDescriptLine::SakuraBalloonAlignment(v)
+            | ShellDescriptLine::KeroBalloonAlignment(v) => {
+                self.balloon_alignment = Some(v.clone())
+            }
+            ShellDescriptLine::SakuraBalloonDontmove(v)
+            | ShellDescriptLine::KeroBalloonDontmove(v)
+            | ShellDescriptLine::CharBalloonDontmove(_, v) => self.balloon_dontmove = Some(*v),
+            // binding
+            ShellDescriptLine::SakuraBindgroupName(v)
+            | ShellDescriptLine::KeroBindgroupName(v)
+            | ShellDescriptLine::CharBindgroupName(_, v) => {
+                self.bindgroups.insert(*v.id(), v.clone());
+            }
+            ShellDescriptLine::SakuraBindgroupDefault(id, v)
+            | ShellDescriptLine::KeroBindgroupDefault(id, v)
+            | ShellDescriptLine::CharBindgroupDefault(_, id, v) => {
+                self.bindgroup_defaults.insert(*id, *v);
+            }
+            ShellDescriptLine::SakuraBindgroupAddid(id, v)
+            | ShellDescriptLine::KeroBindgroupAddid(id, v)
+            | ShellDescriptLine::CharBindgroupAddid(_, id, v) => {
+                self.bindgroup_addids.insert(*id, v.clone());
+            }
+            ShellDescriptLine::SakuraBindoptionGroup(v)
+            | ShellDescriptLine::KeroBindoptionGroup(v)
+            | ShellDescriptLine::CharBindoptionGroup(_, v) => {
+                self.bindoptions.insert(*v.id(), v.clone());
+            }
+            ShellDescriptLine::SakuraMenuitem(i, v)
+            | ShellDescriptLine::KeroMenuitem(i, v)
+            | ShellDescriptLine::CharMenuitem(_, i, v) => {
+                self.menuitems.insert(*i, v.clone());
+            }
+            ShellDescriptLine::SakuraMenuitemex(i, name, v)
+            | ShellDescriptLine::KeroMenuitemex(i, name, v)
+            | ShellDescriptLine::CharMenuitemex(_, i, name, v) => {
+                self.menuitemexes.insert(*i, (name.clone(), v.clone()));
+            }
+            ShellDescriptLine::SakuraMenu(v)
+            | ShellDescriptLine::KeroMenu(v)
+            | ShellDescriptLine::CharMenu(_, v) => self.menu = Some(v.clone()),
+            _ => {}
+        }
+    }
+}
+
+impl CharacterSettings {
+    /// Scope of the character.
+    pub fn scope(&self) -> &CharacterIdType {
+        &self.scope
+    }
+    /// Name of the character.
+    pub fn name(&self) -> &Option<String> {
+        self.config.name()
+    }
+    /// `defaultx` and `defaulty` of the character.
+    pub fn default_position(&self) -> (Option<DesktopPositionType>, Option<DesktopPositionType>) {
+        (*self.config.defaultx(), *self.config.defaulty())
+    }
+    /// `defaultleft` and `defaulttop` of the character.
+    pub fn default_left_top(&self) -> (Option<DesktopPositionType>, Option<DesktopPositionType>) {
+        (*self.config.defaultleft(), *self.config.defaulttop())
+    }
+    /// Alignment to the desktop, falling back to `seriko.alignmenttodesktop`.
+    pub fn alignmenttodesktop(&self) -> &Option<SurfacePosition> {
+        &self.alignmenttodesktop
+    }
+    /// All the merged settings, including balloon and dressup ones.
+    pub fn config(&self) -> &CharacterConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::parse;
+
+    mod resolve {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,Shift_JIS
+type,shell
+name,master
+name,master2
+craftman,ukadog
+
+seriko.alignmenttodesktop,free
+sakura.name,さくら
+sakura.defaultx,10
+sakura.defaultx,20
+kero.balloon.alignment,left
+char2.name,third
+char2.defaulty,-30
+
+menu.font.name,ＭＳ ゴシック
+menu.frame.color.r,10
+menu.frame.color.g,20
+menu.frame.color.b,30
+menu.background.alignment,lefttop+repeat-x
+
+sakura.bindgroup0.name,帽子,麦わら,straw
+sakura.bindgroup0.name,帽子,リボン,ribbon
+sakura.bindgroup0.default,1
+sakura.bindoption0.group,帽子,mustselect
+kero.menuitem0,-
+char2.bindgroup3.addid,4,5
+"#;
+            let result = parse(case).unwrap().resolve();
+            assert_eq!(result.charset(), &Some(Charset::ShiftJIS));
+            assert_eq!(result.name(), &Some("master2".to_string()));
+            assert!(*result.is_shell());
+            assert!(!*result.is_menu_hidden());
+            assert_eq!(result.craftman(), &Some("ukadog".to_string()));
+            assert_eq!(result.homeurl(), &None);
+            assert_eq!(
+                result.seriko().alignmenttodesktop(),
+                &Some(SurfacePosition::Free)
+            );
+
+            assert_eq!(result.sakura().name(), &Some("さくら".to_string()));
+            assert_eq!(result.sakura().defaultx(), &Some(20));
+            assert_eq!(
+                result.kero().balloon_alignment(),
+                &Some(BalloonPosition::Left)
+            );
+            assert_eq!(result.scopes(), vec![0, 1, 2]);
+            let char2 = result.chars().get(&2).unwrap();
+            assert_eq!(char2.name(), &Some("third".to_string()));
+            assert_eq!(char2.defaulty(), &Some(-30));
+            assert_eq!(char2.bindgroup_addids().get(&3), Some(&vec![4, 5]));
+
+            assert_eq!(
+                result.menu().font_name(),
+                &Some("ＭＳ ゴシック".to_string())
+            );
+            assert_eq!(result.menu().frame_color().rgb(), Some((10, 20, 30)));
+            assert_eq!(result.menu().separator_color().rgb(), None);
+            assert_eq!(
+                result.menu().background_alignment(),
+                &Some((
+                    MenuPositionForegroundBackgroundBase::Lefttop,
+                    Some(MenuPositionForegroundBackgroundRepeat::RepeatX),
+                    None
+                ))
+            );
+
+            assert_eq!(
+                result
+                    .sakura()
+                    .bindgroups()
+                    .get(&0)
+                    .map(|v| v.part_name().as_str()),
+                Some("リボン")
+            );
+            assert_eq!(result.sakura().bindgroup_defaults().get(&0), Some(&1));
+            assert!(result
+                .sakura()
+                .bindoptions()
+                .get(&0)
+                .unwrap()
+                .is_mustselect());
+            assert_eq!(result.kero().menuitems().get(&0), Some(&BindMenuItem::Line));
+        }
+
+        #[test]
+        fn success_when_empty() {
+            let result = ShellDescript::new(vec![]).resolve();
+            assert_eq!(result, ShellConfig::default());
+            assert_eq!(result.scopes(), vec![0, 1]);
+        }
+    }
+
+    mod character {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = r#"charset,UTF-8
+seriko.alignmenttodesktop,bottom
+char0.name,zero
+char0.defaultx,10
+char0.defaulty,20
+sakura.defaultx,30
+char0.bindgroup0.name,帽子,麦わら
+sakura.bindgroup0.name,帽子,リボン
+sakura.bindgroup1.name,服,エプロン
+kero.seriko.alignmenttodesktop,free
+kero.balloon.offsetx,-20
+char2.name,third
+char2.bindgroup0.name,腕,腕輪
+"#;
+            let descript = parse(case).unwrap();
+
+            let sakura = descript.character(0);
+            assert_eq!(sakura.scope(), &0);
+            assert_eq!(sakura.name(), &Some("zero".to_string()));
+            assert_eq!(sakura.default_position(), (Some(30), Some(20)));
+            assert_eq!(sakura.alignmenttodesktop(), &Some(SurfacePosition::Bottom));
+            assert_eq!(sakura.config().bindgroups().len(), 2);
+            assert_eq!(
+                sakura
+                    .config()
+                    .bindgroups()
+                    .get(&0)
+                    .map(|v| v.part_name().as_str()),
+                Some("リボン")
+            );
+
+            let kero = descript.character(1);
+            assert_eq!(kero.name(), &None);
+            assert_eq!(kero.alignmenttodesktop(), &Some(SurfacePosition::Free));
+            assert_eq!(kero.config().balloon_offsetx(), &Some(-20));
+
+            let char2 = descript.character(2);
+            assert_eq!(char2.name(), &Some("third".to_string()));
+            assert_eq!(char2.alignmenttodesktop(), &Some(SurfacePosition::Bottom));
+            assert_eq!(char2.config().bindgroups().len(), 1);
+        }
+
+        #[test]
+        fn success_when_char_given_last() {
+            let case = r#"charset,UTF-8
+sakura.defaultx,30
+char0.defaultx,10
+kero.name,unyu
+char1.name,kero
+kero.balloon.offsetx,-20
+sakura.bindgroup0.name,帽子,リボン
+char0.bindgroup0.name,帽子,麦わら
+"#;
+            let descript = parse(case).unwrap();
+
+            let sakura = descript.character(0);
+            assert_eq!(sakura.default_position(), (Some(10), None));
+            assert_eq!(
+                sakura
+                    .config()
+                    .bindgroups()
+                    .get(&0)
+                    .map(|v| v.part_name().as_str()),
+                Some("麦わら")
+            );
+
+            let kero = descript.character(1);
+            assert_eq!(kero.name(), &Some("kero".to_string()));
+            assert_eq!(kero.config().balloon_offsetx(), &Some(-20));
+
+            let config = descript.resolve();
+            assert_eq!(config.sakura(), sakura.config());
+            assert_eq!(config.kero(), kero.config());
+        }
+
+        #[test]
+        fn success_when_undefined_scope() {
+            let case = "charset,UTF-8\r\n";
+            let result = parse(case).unwrap().character(5);
+            assert_eq!(result.scope(), &5);
+            assert_eq!(result.name(), &None);
+            assert_eq!(result.default_position(), (None, None));
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Helpers shared by the tests.
+
+use std::{fs, path::PathBuf};
+
+/// Creates a shell directory for a test with empty `files` under the temporary directory.
+pub(crate) fn shell_dir(name: &str, files: &[&str]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "shell-parser-descript-rs-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for file in files.iter() {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+    dir
+}
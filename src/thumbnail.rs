@@ -0,0 +1,236 @@
+//! Resolves thumbnail names of bindgroups to image files in the shell directory.
+
+use std::path::{Path, PathBuf};
+
+use crate::assets::resolve_asset_path;
+use crate::ast::{
+    AnimationIdType, CharacterIdType, LineContainer, ShellDescript, ShellDescriptLine, Span,
+};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::dressup::DressupPart;
+
+/// Extensions tried in order for a thumbnail name.
+///
+/// This list and its order are this crate's own choice of common image formats,
+/// not taken from the baseware.
+pub const THUMBNAIL_EXTENSIONS: [&str; 7] = ["png", "bmp", "ico", "jpg", "jpeg", "gif", "webp"];
+
+/// Thumbnail of a bindgroup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thumbnail {
+    scope: CharacterIdType,
+    id: AnimationIdType,
+    name: String,
+    index: usize,
+    span: Option<Span>,
+    path: Option<PathBuf>,
+}
+
+impl ShellDescript {
+    /// Thumbnails of the bindgroups, in order of the lines, each resolved in the shell directory `dir`.
+    ///
+    /// See [`resolve_thumbnail_path`] for how the names are resolved.
+    pub fn thumbnails<P: AsRef<Path>>(&self, dir: P) -> Vec<Thumbnail> {
+        self.lines()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let (scope, props) = match line {
+                    LineContainer::Body(ShellDescriptLine::SakuraBindgroupName(v)) => (0, v),
+                    LineContainer::Body(ShellDescriptLine::KeroBindgroupName(v)) => (1, v),
+                    LineContainer::Body(ShellDescriptLine::CharBindgroupName(c, v)) => (*c, v),
+                    _ => return None,
+                };
+                let name = props.thumbnail_name().as_ref()?;
+                Some(Thumbnail {
+                    scope,
+                    id: *props.id(),
+                    name: name.clone(),
+                    index,
+                    span: self.span(index).map(|v| v.value().unwrap_or(*v.line())),
+                    path: resolve_thumbnail_path(dir.as_ref(), name),
+                })
+            })
+            .collect()
+    }
+
+    /// Reports bindgroups whose thumbnail is not found in the shell directory `dir`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use shell_parser_descript_rs::load_shell_dir;
+    ///
+    /// let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_target/descript");
+    /// let shell_descript = load_shell_dir(&dir).unwrap();
+    ///
+    /// let result = shell_descript.check_thumbnails(&dir);
+    /// assert_eq!(result.len(), 4);
+    /// assert_eq!(result[0].code(), "missing-thumbnail");
+    /// assert_eq!(result[0].index(), &Some(19));
+    /// assert_eq!(
+    ///     result[0].message(),
+    ///     "sakura.bindgroup0.name has thumbnail apron which is not found"
+    /// );
+    /// ```
+    pub fn check_thumbnails<P: AsRef<Path>>(&self, dir: P) -> Vec<Diagnostic> {
+        self.thumbnails(dir)
+            .into_iter()
+            .filter(|v| v.path.is_none())
+            .map(|v| {
+                let key = match &self.lines()[v.index] {
+                    LineContainer::Body(line) => line.key(),
+                    _ => String::new(),
+                };
+                Diagnostic::new(
+                    Severity::Warning,
+                    "missing-thumbnail",
+                    format!("{} has thumbnail {} which is not found", key, v.name),
+                    Some(v.index),
+                    v.span,
+                )
+            })
+            .collect()
+    }
+}
+
+impl DressupPart {
+    /// Image file of the thumbnail in the shell directory `dir`.
+    pub fn thumbnail_path<P: AsRef<Path>>(&self, dir: P) -> Option<PathBuf> {
+        self.thumbnail_name()
+            .as_ref()
+            .and_then(|v| resolve_thumbnail_path(dir.as_ref(), v))
+    }
+}
+
+/// Finds the image file of the thumbnail `name` in `dir`.
+///
+/// `name` is tried with each of [`THUMBNAIL_EXTENSIONS`] appended, and as it is
+/// only when it already ends with one of them.
+/// Directories and case are handled as in [`resolve_asset_path`].
+pub fn resolve_thumbnail_path(dir: &Path, name: &str) -> Option<PathBuf> {
+    let has_extension = name.rsplit_once('.').is_some_and(|(_, ext)| {
+        THUMBNAIL_EXTENSIONS
+            .iter()
+            .any(|v| v.eq_ignore_ascii_case(ext))
+    });
+    THUMBNAIL_EXTENSIONS
+        .iter()
+        .find_map(|ext| resolve_asset_path(dir, &format!("{}.{}", name, ext)))
+        .or_else(|| {
+            has_extension
+                .then(|| resolve_asset_path(dir, name))
+                .flatten()
+        })
+}
+
+impl Thumbnail {
+    pub fn scope(&self) -> &CharacterIdType {
+        &self.scope
+    }
+    /// Id of the bindgroup.
+    pub fn id(&self) -> &AnimationIdType {
+        &self.id
+    }
+    /// Name as written in the line.
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    /// Index of the `bindgroupN.name` line in [`ShellDescript::lines`].
+    pub fn index(&self) -> &usize {
+        &self.index
+    }
+    /// Span of the value in the parsed input.
+    pub fn span(&self) -> &Option<Span> {
+        &self.span
+    }
+    /// Path of the image file found, `None` when it is missing.
+    pub fn path(&self) -> &Option<PathBuf> {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use crate::parse::parse;
+    use crate::test_support::shell_dir;
+
+    const CASE: &str = r#"charset,UTF-8
+sakura.bindgroup0.name,服,エプロン,apron
+sakura.bindgroup1.name,服,ドレス,dress
+sakura.bindgroup2.name,服,水着
+kero.bindgroup0.name,首,ネクタイ,thumb\tie.png
+char2.bindgroup3.name,頭,リボン,ribbonwhite
+"#;
+
+    mod thumbnails {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let dir = shell_dir(
+                "thumbnails",
+                &["apron.BMP", "apron.ico", "Thumb/tie.png", "ribbonwhite.png"],
+            );
+            let descript = parse(CASE).unwrap();
+
+            let result = descript.thumbnails(&dir);
+            let found: Vec<(CharacterIdType, AnimationIdType, Option<PathBuf>)> = result
+                .iter()
+                .map(|v| (*v.scope(), *v.id(), v.path().clone()))
+                .collect();
+            assert_eq!(
+                found,
+                vec![
+                    (0, 0, Some(dir.join("apron.BMP"))),
+                    (0, 1, None),
+                    (1, 0, Some(dir.join("Thumb").join("tie.png"))),
+                    (2, 3, Some(dir.join("ribbonwhite.png"))),
+                ]
+            );
+
+            let result = descript.check_thumbnails(&dir);
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].index(), &Some(2));
+            assert_eq!(
+                result[0].message(),
+                "sakura.bindgroup1.name has thumbnail dress which is not found"
+            );
+
+            let (part, _) = descript.dressup(0);
+            assert_eq!(
+                part.part(0).unwrap().thumbnail_path(&dir),
+                Some(dir.join("apron.BMP"))
+            );
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    mod resolve_thumbnail_path {
+        use super::*;
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let dir = shell_dir("thumbnail-path", &["apron.txt", "dress", "hat.PNG"]);
+            assert_eq!(resolve_thumbnail_path(&dir, "apron"), None);
+            assert_eq!(resolve_thumbnail_path(&dir, "apron.png"), None);
+            assert_eq!(resolve_thumbnail_path(&dir, "apron.txt"), None);
+            assert_eq!(resolve_thumbnail_path(&dir, "DRESS"), None);
+            assert_eq!(
+                resolve_thumbnail_path(&dir, "hat.png"),
+                Some(dir.join("hat.PNG"))
+            );
+            assert_eq!(
+                resolve_thumbnail_path(&dir, "HAT"),
+                Some(dir.join("hat.PNG"))
+            );
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+}
@@ -0,0 +1,895 @@
+//! Writes a [`ShellDescript`] back to descript.txt text.
+//!
+//! [`ShellDescript`]: crate::ast::ShellDescript
+
+use std::{error::Error, fmt};
+
+use encoding_rs::{EncoderResult, Encoding, EUC_JP, ISO_2022_JP, SHIFT_JIS};
+use shell_parser_common_rs::charset::Charset;
+
+use crate::ast::{
+    BalloonPosition, BindGroupNameProps, BindMenuItem, BindMenuVisibility, BindOption,
+    LineContainer, MenuPositionForegroundBackgroundBase, MenuPositionForegroundBackgroundRepeat,
+    MenuPositionSidebarBase, MenuPositionSidebarRepeat, Newline, ShellDescript, ShellDescriptLine,
+    SurfacePosition,
+};
+use crate::parse::parse_for_decode;
+
+/// Line terminator used when writing lines.
+pub const NEWLINE: &str = "\r\n";
+
+/// Writes each line followed by [`NEWLINE`].
+///
+/// Lines read by [`parse_lossless`] are written with their original text and terminator
+/// instead, and other lines follow the terminator of the first of those.
+///
+/// [`parse_lossless`]: crate::parse::parse_lossless
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::parse;
+///
+/// let case = "charset,UTF-8\r\ntype,shell\r\n\r\n//comment\r\nsakura.defaultx,-20\r\n";
+///
+/// let shell_descript = parse(case).unwrap();
+/// assert_eq!(shell_descript.to_string(), case);
+/// assert_eq!(parse(&shell_descript.to_string()).unwrap(), shell_descript);
+/// ```
+impl fmt::Display for ShellDescript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let newline = self
+            .sources()
+            .iter()
+            .flatten()
+            .map(|v| v.newline())
+            .find(|v| **v != Newline::Eof)
+            .map(|v| v.as_str())
+            .unwrap_or(NEWLINE);
+        let last = self.lines().len().saturating_sub(1);
+
+        for (i, (line, source)) in self.lines().iter().zip(self.sources()).enumerate() {
+            match source {
+                Some(v) if *v.newline() != Newline::Eof || i == last => {
+                    write!(f, "{}{}", v.text(), v.newline().as_str())?
+                }
+                Some(v) => write!(f, "{}{}", v.text(), newline)?,
+                None => write!(f, "{}{}", line, newline)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the line without its terminator.
+impl fmt::Display for LineContainer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineContainer::EmptyLine => Ok(()),
+            LineContainer::CommentLine(v) => write!(f, "{}", v),
+            LineContainer::Body(v) => write!(f, "{}", v),
+            LineContainer::Invalid { key, value, .. } => write!(f, "{},{}", key, value),
+            LineContainer::Unknown { key, values } => write!(f, "{},{}", key, values.join(",")),
+        }
+    }
+}
+
+/// Writes the line in its canonical `key,value` form.
+impl fmt::Display for ShellDescriptLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.key(), self.value())
+    }
+}
+
+impl ShellDescriptLine {
+    /// Returns the key part of the line, e.g. `sakura.bindgroup5.name`.
+    pub fn key(&self) -> String {
+        match self {
+            // base
+            ShellDescriptLine::Charset(_) => "charset".to_string(),
+            ShellDescriptLine::Name(_) => "name".to_string(),
+            ShellDescriptLine::Id(_) => "id".to_string(),
+            ShellDescriptLine::Type => "type".to_string(),
+            ShellDescriptLine::Craftman(_) => "craftman".to_string(),
+            ShellDescriptLine::Craftmanw(_) => "craftmanw".to_string(),
+            ShellDescriptLine::Craftmanurl(_) => "craftmanurl".to_string(),
+            ShellDescriptLine::Homeurl(_) => "homeurl".to_string(),
+            ShellDescriptLine::Readme(_) => "readme".to_string(),
+            ShellDescriptLine::ReadmeCharset(_) => "readme.charset".to_string(),
+            ShellDescriptLine::MenuHidden => "menu".to_string(),
+            ShellDescriptLine::SakuraName(_) => "sakura.name".to_string(),
+            ShellDescriptLine::SakuraName2(_) => "sakura.name2".to_string(),
+            ShellDescriptLine::KeroName(_) => "kero.name".to_string(),
+            ShellDescriptLine::CharName(c, _) => format!("char{}.name", c),
+            // shell representation
+            ShellDescriptLine::SerikoZOrder(_) => "seriko.zorder".to_string(),
+            ShellDescriptLine::SerikoStickyWindow(_) => "seriko.sticky-window".to_string(),
+            ShellDescriptLine::SerikoAlignmenttodesktop(_) => {
+                "seriko.alignmenttodesktop".to_string()
+            }
+            ShellDescriptLine::SakuraSerikoAlignmenttodesktop(_) => {
+                "sakura.seriko.alignmenttodesktop".to_string()
+            }
+            ShellDescriptLine::KeroSerikoAlignmenttodesktop(_) => {
+                "kero.seriko.alignmenttodesktop".to_string()
+            }
+            ShellDescriptLine::CharSerikoAlignmenttodesktop(c, _) => {
+                format!("char{}.seriko.alignmenttodesktop", c)
+            }
+            ShellDescriptLine::SakuraDefaultx(_) => "sakura.defaultx".to_string(),
+            ShellDescriptLine::KeroDefaultx(_) => "kero.defaultx".to_string(),
+            ShellDescriptLine::CharDefaultx(c, _) => format!("char{}.defaultx", c),
+            ShellDescriptLine::SakuraDefaulty(_) => "sakura.defaulty".to_string(),
+            ShellDescriptLine::KeroDefaulty(_) => "kero.defaulty".to_string(),
+            ShellDescriptLine::CharDefaulty(c, _) => format!("char{}.defaulty", c),
+            ShellDescriptLine::SakuraDefaultleft(_) => "sakura.defaultleft".to_string(),
+            ShellDescriptLine::KeroDefaultleft(_) => "kero.defaultleft".to_string(),
+            ShellDescriptLine::CharDefaultleft(c, _) => format!("char{}.defaultleft", c),
+            ShellDescriptLine::SakuraDefaulttop(_) => "sakura.defaulttop".to_string(),
+            ShellDescriptLine::KeroDefaulttop(_) => "kero.defaulttop".to_string(),
+            ShellDescriptLine::CharDefaulttop(c, _) => format!("char{}.defaulttop", c),
+            // balloon representation
+            ShellDescriptLine::SakuraBalloonOffsetx(_) => "sakura.balloon.offsetx".to_string(),
+            ShellDescriptLine::SakuraBalloonOffsety(_) => "sakura.balloon.offsety".to_string(),
+            ShellDescriptLine::KeroBalloonOffsetx(_) => "kero.balloon.offsetx".to_string(),
+            ShellDescriptLine::KeroBalloonOffsety(_) => "kero.balloon.offsety".to_string(),
+            ShellDescriptLine::SakuraBalloonAlignment(_) => "sakura.balloon.alignment".to_string(),
+            ShellDescriptLine::KeroBalloonAlignment(_) => "kero.balloon.alignment".to_string(),
+            ShellDescriptLine::SakuraBalloonDontmove(_) => "sakura.balloon.dontmove".to_string(),
+            ShellDescriptLine::KeroBalloonDontmove(_) => "kero.balloon.dontmove".to_string(),
+            ShellDescriptLine::CharBalloonDontmove(c, _) => format!("char{}.balloon.dontmove", c),
+            // menu
+            ShellDescriptLine::MenuFontName(_) => "menu.font.name".to_string(),
+            ShellDescriptLine::MenuFontHeight(_) => "menu.font.height".to_string(),
+            ShellDescriptLine::MenuBackgroundBitmapFilename(_) => {
+                "menu.background.bitmap.filename".to_string()
+            }
+            ShellDescriptLine::MenuForegroundBitmapFilename(_) => {
+                "menu.foreground.bitmap.filename".to_string()
+            }
+            ShellDescriptLine::MenuSidebarBitmapFilename(_) => {
+                "menu.sidebar.bitmap.filename".to_string()
+            }
+            ShellDescriptLine::MenuBackgroundFontColorR(_) => {
+                "menu.background.font.color.r".to_string()
+            }
+            ShellDescriptLine::MenuBackgroundFontColorG(_) => {
+                "menu.background.font.color.g".to_string()
+            }
+            ShellDescriptLine::MenuBackgroundFontColorB(_) => {
+                "menu.background.font.color.b".to_string()
+            }
+            ShellDescriptLine::MenuForegroundFontColorR(_) => {
+                "menu.foreground.font.color.r".to_string()
+            }
+            ShellDescriptLine::MenuForegroundFontColorG(_) => {
+                "menu.foreground.font.color.g".to_string()
+            }
+            ShellDescriptLine::MenuForegroundFontColorB(_) => {
+                "menu.foreground.font.color.b".to_string()
+            }
+            ShellDescriptLine::MenuSeparatorColorR(_) => "menu.separator.color.r".to_string(),
+            ShellDescriptLine::MenuSeparatorColorG(_) => "menu.separator.color.g".to_string(),
+            ShellDescriptLine::MenuSeparatorColorB(_) => "menu.separator.color.b".to_string(),
+            ShellDescriptLine::MenuFrameColorR(_) => "menu.frame.color.r".to_string(),
+            ShellDescriptLine::MenuFrameColorG(_) => "menu.frame.color.g".to_string(),
+            ShellDescriptLine::MenuFrameColorB(_) => "menu.frame.color.b".to_string(),
+            ShellDescriptLine::MenuDisableFontColorR(_) => "menu.disable.font.color.r".to_string(),
+            ShellDescriptLine::MenuDisableFontColorG(_) => "menu.disable.font.color.g".to_string(),
+            ShellDescriptLine::MenuDisableFontColorB(_) => "menu.disable.font.color.b".to_string(),
+            ShellDescriptLine::MenuBackgroundAlignment(_, _, _) => {
+                "menu.background.alignment".to_string()
+            }
+            ShellDescriptLine::MenuForegroundAlignment(_, _, _) => {
+                "menu.foreground.alignment".to_string()
+            }
+            ShellDescriptLine::MenuSidebarAlignment(_, _) => "menu.sidebar.alignment".to_string(),
+            // binding
+            ShellDescriptLine::SakuraBindgroupName(v) => {
+                format!("sakura.bindgroup{}.name", v.id())
+            }
+            ShellDescriptLine::SakuraBindgroupDefault(id, _) => {
+                format!("sakura.bindgroup{}.default", id)
+            }
+            ShellDescriptLine::SakuraBindgroupAddid(id, _) => {
+                format!("sakura.bindgroup{}.addid", id)
+            }
+            ShellDescriptLine::SakuraBindoptionGroup(v) => {
+                format!("sakura.bindoption{}.group", v.id())
+            }
+            ShellDescriptLine::SakuraMenuitem(i, _) => format!("sakura.menuitem{}", i),
+            ShellDescriptLine::SakuraMenuitemex(i, _, _) => format!("sakura.menuitemex{}", i),
+            ShellDescriptLine::SakuraMenu(_) => "sakura.menu".to_string(),
+            ShellDescriptLine::KeroBindgroupName(v) => format!("kero.bindgroup{}.name", v.id()),
+            ShellDescriptLine::KeroBindgroupDefault(id, _) => {
+                format!("kero.bindgroup{}.default", id)
+            }
+            ShellDescriptLine::KeroBindgroupAddid(id, _) => format!("kero.bindgroup{}.addid", id),
+            ShellDescriptLine::KeroBindoptionGroup(v) => {
+                format!("kero.bindoption{}.group", v.id())
+            }
+            ShellDescriptLine::KeroMenuitem(i, _) => format!("kero.menuitem{}", i),
+            ShellDescriptLine::KeroMenuitemex(i, _, _) => format!("kero.menuitemex{}", i),
+            ShellDescriptLine::KeroMenu(_) => "kero.menu".to_string(),
+            ShellDescriptLine::CharBindgroupName(c, v) => {
+                format!("char{}.bindgroup{}.name", c, v.id())
+            }
+            ShellDescriptLine::CharBindgroupDefault(c, id, _) => {
+                format!("char{}.bindgroup{}.default", c, id)
+            }
+            ShellDescriptLine::CharBindgroupAddid(c, id, _) => {
+                format!("char{}.bindgroup{}.addid", c, id)
+            }
+            ShellDescriptLine::CharBindoptionGroup(c, v) => {
+                format!("char{}.bindoption{}.group", c, v.id())
+            }
+            ShellDescriptLine::CharMenuitem(c, i, _) => format!("char{}.menuitem{}", c, i),
+            ShellDescriptLine::CharMenuitemex(c, i, _, _) => format!("char{}.menuitemex{}", c, i),
+            ShellDescriptLine::CharMenu(c, _) => format!("char{}.menu", c),
+            // alpha
+            ShellDescriptLine::SerikoPaintTransparentRegionBlack(_) => {
+                "seriko.paint_transparent_region_black".to_string()
+            }
+            ShellDescriptLine::SerikoUseSelfAlpha(_) => "seriko.use_self_alpha".to_string(),
+        }
+    }
+
+    /// Returns the value part of the line, e.g. `category,part,thumbnail`.
+    pub fn value(&self) -> String {
+        match self {
+            ShellDescriptLine::Charset(v) | ShellDescriptLine::ReadmeCharset(v) => {
+                charset_name(v).to_string()
+            }
+            ShellDescriptLine::Type => "shell".to_string(),
+            ShellDescriptLine::MenuHidden => "hidden".to_string(),
+            ShellDescriptLine::Name(v)
+            | ShellDescriptLine::Id(v)
+            | ShellDescriptLine::Craftman(v)
+            | ShellDescriptLine::Craftmanw(v)
+            | ShellDescriptLine::Craftmanurl(v)
+            | ShellDescriptLine::Homeurl(v)
+            | ShellDescriptLine::Readme(v)
+            | ShellDescriptLine::SakuraName(v)
+            | ShellDescriptLine::SakuraName2(v)
+            | ShellDescriptLine::KeroName(v)
+            | ShellDescriptLine::CharName(_, v)
+            | ShellDescriptLine::MenuFontName(v)
+            | ShellDescriptLine::MenuBackgroundBitmapFilename(v)
+            | ShellDescriptLine::MenuForegroundBitmapFilename(v)
+            | ShellDescriptLine::MenuSidebarBitmapFilename(v) => v.clone(),
+            ShellDescriptLine::SerikoZOrder(v) | ShellDescriptLine::SerikoStickyWindow(v) => {
+                join_ids(v)
+            }
+            ShellDescriptLine::SerikoAlignmenttodesktop(v)
+            | ShellDescriptLine::SakuraSerikoAlignmenttodesktop(v)
+            | ShellDescriptLine::KeroSerikoAlignmenttodesktop(v)
+            | ShellDescriptLine::CharSerikoAlignmenttodesktop(_, v) => v.to_string(),
+            ShellDescriptLine::SakuraDefaultx(v)
+            | ShellDescriptLine::KeroDefaultx(v)
+            | ShellDescriptLine::CharDefaultx(_, v)
+            | ShellDescriptLine::SakuraDefaulty(v)
+            | ShellDescriptLine::KeroDefaulty(v)
+            | ShellDescriptLine::CharDefaulty(_, v)
+            | ShellDescriptLine::SakuraDefaultleft(v)
+            | ShellDescriptLine::KeroDefaultleft(v)
+            | ShellDescriptLine::CharDefaultleft(_, v)
+            | ShellDescriptLine::SakuraDefaulttop(v)
+            | ShellDescriptLine::KeroDefaulttop(v)
+            | ShellDescriptLine::CharDefaulttop(_, v)
+            | ShellDescriptLine::SakuraBalloonOffsetx(v)
+            | ShellDescriptLine::SakuraBalloonOffsety(v)
+            | ShellDescriptLine::KeroBalloonOffsetx(v)
+            | ShellDescriptLine::KeroBalloonOffsety(v) => v.to_string(),
+            ShellDescriptLine::SakuraBalloonAlignment(v)
+            | ShellDescriptLine::KeroBalloonAlignment(v) => v.to_string(),
+            ShellDescriptLine::SakuraBalloonDontmove(v)
+            | ShellDescriptLine::KeroBalloonDontmove(v)
+            | ShellDescriptLine::CharBalloonDontmove(_, v)
+            | ShellDescriptLine::SakuraBindgroupDefault(_, v)
+            | ShellDescriptLine::KeroBindgroupDefault(_, v)
+            | ShellDescriptLine::CharBindgroupDefault(_, _, v)
+            | ShellDescriptLine::SerikoPaintTransparentRegionBlack(v)
+            | ShellDescriptLine::SerikoUseSelfAlpha(v) => v.to_string(),
+            ShellDescriptLine::MenuFontHeight(v) => v.to_string(),
+            ShellDescriptLine::MenuBackgroundFontColorR(v)
+            | ShellDescriptLine::MenuBackgroundFontColorG(v)
+            | ShellDescriptLine::MenuBackgroundFontColorB(v)
+            | ShellDescriptLine::MenuForegroundFontColorR(v)
+            | ShellDescriptLine::MenuForegroundFontColorG(v)
+            | ShellDescriptLine::MenuForegroundFontColorB(v)
+            | ShellDescriptLine::MenuSeparatorColorR(v)
+            | ShellDescriptLine::MenuSeparatorColorG(v)
+            | ShellDescriptLine::MenuSeparatorColorB(v)
+            | ShellDescriptLine::MenuFrameColorR(v)
+            | ShellDescriptLine::MenuFrameColorG(v)
+            | ShellDescriptLine::MenuFrameColorB(v)
+            | ShellDescriptLine::MenuDisableFontColorR(v)
+            | ShellDescriptLine::MenuDisableFontColorG(v)
+            | ShellDescriptLine::MenuDisableFontColorB(v) => v.to_string(),
+            ShellDescriptLine::MenuBackgroundAlignment(b, r1, r2)
+            | ShellDescriptLine::MenuForegroundAlignment(b, r1, r2) => {
+                let mut s = b.to_string();
+                for r in [r1, r2].into_iter().flatten() {
+                    s.push('+');
+                    s.push_str(&r.to_string());
+                }
+                s
+            }
+            ShellDescriptLine::MenuSidebarAlignment(b, r) => match r {
+                Some(r) => format!("{}+{}", b, r),
+                None => b.to_string(),
+            },
+            ShellDescriptLine::SakuraBindgroupName(v)
+            | ShellDescriptLine::KeroBindgroupName(v)
+            | ShellDescriptLine::CharBindgroupName(_, v) => v.to_string(),
+            ShellDescriptLine::SakuraBindgroupAddid(_, v)
+            | ShellDescriptLine::KeroBindgroupAddid(_, v)
+            | ShellDescriptLine::CharBindgroupAddid(_, _, v) => join_ids(v),
+            ShellDescriptLine::SakuraBindoptionGroup(v)
+            | ShellDescriptLine::KeroBindoptionGroup(v)
+            | ShellDescriptLine::CharBindoptionGroup(_, v) => v.to_string(),
+            ShellDescriptLine::SakuraMenuitem(_, v)
+            | ShellDescriptLine::KeroMenuitem(_, v)
+            | ShellDescriptLine::CharMenuitem(_, _, v) => v.to_string(),
+            ShellDescriptLine::SakuraMenuitemex(_, name, v)
+            | ShellDescriptLine::KeroMenuitemex(_, name, v)
+            | ShellDescriptLine::CharMenuitemex(_, _, name, v) => format!("{},{}", name, v),
+            ShellDescriptLine::SakuraMenu(v)
+            | ShellDescriptLine::KeroMenu(v)
+            | ShellDescriptLine::CharMenu(_, v) => v.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SurfacePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SurfacePosition::Top => "top",
+            SurfacePosition::Bottom => "bottom",
+            SurfacePosition::Free => "free",
+        })
+    }
+}
+
+impl fmt::Display for BalloonPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BalloonPosition::None => "none",
+            BalloonPosition::Left => "left",
+            BalloonPosition::Right => "right",
+        })
+    }
+}
+
+impl fmt::Display for MenuPositionForegroundBackgroundBase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MenuPositionForegroundBackgroundBase::Lefttop => "lefttop",
+            MenuPositionForegroundBackgroundBase::Centertop => "centertop",
+            MenuPositionForegroundBackgroundBase::Righttop => "righttop",
+            MenuPositionForegroundBackgroundBase::Leftbottom => "leftbottom",
+            MenuPositionForegroundBackgroundBase::Centerbottom => "centerbottom",
+            MenuPositionForegroundBackgroundBase::Rightbottom => "rightbottom",
+        })
+    }
+}
+
+/// Writes the repeat option without its leading `+`.
+impl fmt::Display for MenuPositionForegroundBackgroundRepeat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MenuPositionForegroundBackgroundRepeat::RepeatX => "repeat-x",
+            MenuPositionForegroundBackgroundRepeat::RepeatY => "repeat-y",
+        })
+    }
+}
+
+impl fmt::Display for MenuPositionSidebarBase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MenuPositionSidebarBase::Top => "top",
+            MenuPositionSidebarBase::Bottom => "bottom",
+        })
+    }
+}
+
+/// Writes the repeat option without its leading `+`.
+impl fmt::Display for MenuPositionSidebarRepeat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MenuPositionSidebarRepeat::RepeatY => "repeat-y",
+        })
+    }
+}
+
+/// Writes `category,part_name` and the thumbnail name if any.
+impl fmt::Display for BindGroupNameProps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.category(), self.part_name())?;
+        if let Some(v) = self.thumbnail_name() {
+            write!(f, ",{}", v)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `category,` followed by the options joined with `+`.
+impl fmt::Display for BindOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut options = Vec::new();
+        if *self.is_mustselect() {
+            options.push("mustselect");
+        }
+        if *self.is_multiple() {
+            options.push("multiple");
+        }
+        write!(f, "{},{}", self.category(), options.join("+"))
+    }
+}
+
+impl fmt::Display for BindMenuItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindMenuItem::Line => f.write_str("-"),
+            BindMenuItem::Id(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl fmt::Display for BindMenuVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BindMenuVisibility::Auto => "auto",
+            BindMenuVisibility::Hidden => "hidden",
+        })
+    }
+}
+
+/// Returns the name of `charset` as written in descript.txt.
+pub fn charset_name(charset: &Charset) -> &'static str {
+    match charset {
+        Charset::ASCII => "ASCII",
+        Charset::ShiftJIS => "Shift_JIS",
+        Charset::ISO2022JP => "ISO-2022-JP",
+        Charset::EUCJP => "EUC-JP",
+        Charset::UTF8 => "UTF-8",
+        Charset::Default => "default",
+    }
+}
+
+/// Error from [`encode_bytes`] and [`write_bytes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodeError {
+    charset: Charset,
+    unmappables: Vec<Unmappable>,
+}
+
+/// A character which cannot be represented in the target charset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unmappable {
+    character: char,
+    offset: usize,
+}
+
+impl EncodeError {
+    pub fn charset(&self) -> &Charset {
+        &self.charset
+    }
+    pub fn unmappables(&self) -> &Vec<Unmappable> {
+        &self.unmappables
+    }
+}
+
+impl Unmappable {
+    pub fn character(&self) -> &char {
+        &self.character
+    }
+    /// Byte offset of the character in the written text.
+    pub fn offset(&self) -> &usize {
+        &self.offset
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot encode {} character(s) to {}:",
+            self.unmappables.len(),
+            charset_name(&self.charset)
+        )?;
+        for v in self.unmappables.iter() {
+            write!(f, " {:?} at {}", v.character, v.offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for EncodeError {}
+
+/// Encodes `&str` to bytes in the charset of its `charset` line.
+///
+/// Without `charset` line, `input` is encoded to Shift_JIS as the baseware reads it.
+///
+/// # Example
+///
+/// ```
+/// use shell_parser_descript_rs::{decode_bytes, encode_bytes};
+///
+/// let case = "charset,Shift_JIS\r\ncraftmanw,うか犬\r\n";
+///
+/// let bytes = match encode_bytes(case) {
+///     Ok(v) => v,
+///     Err(e) => {
+///         eprintln!("{}", e);
+///         return;
+///     }
+/// };
+/// assert_eq!(decode_bytes(&bytes).unwrap(), case);
+///
+/// let case = "charset,Shift_JIS\r\ncraftmanw,\u{1F415}\r\n";
+/// assert!(encode_bytes(case).is_err());
+/// ```
+pub fn encode_bytes(input: &str) -> Result<Vec<u8>, EncodeError> {
+    let charset = parse_for_decode(input)
+        .map(|(_, v)| v)
+        .unwrap_or(Charset::Default);
+
+    encode_with_charset(input, &charset)
+}
+
+/// Writes a [`ShellDescript`] and encodes it to bytes in the charset of its first `charset` line.
+///
+/// Without `charset` line, it is encoded to Shift_JIS as the baseware reads it.
+pub fn write_bytes(shell_descript: &ShellDescript) -> Result<Vec<u8>, EncodeError> {
+    let charset = shell_descript
+        .lines()
+        .iter()
+        .find_map(|v| match v {
+            LineContainer::Body(ShellDescriptLine::Charset(c)) => Some(c.clone()),
+            _ => None,
+        })
+        .unwrap_or(Charset::Default);
+
+    encode_with_charset(&shell_descript.to_string(), &charset)
+}
+
+/// Encodes `&str` to bytes in `charset`, reporting every character which cannot be represented.
+pub fn encode_with_charset(input: &str, charset: &Charset) -> Result<Vec<u8>, EncodeError> {
+    let result = match charset {
+        Charset::ASCII => encode_ascii(input),
+        Charset::UTF8 => Ok(input.as_bytes().to_vec()),
+        Charset::ShiftJIS | Charset::Default => encode_with_encoding(input, SHIFT_JIS),
+        Charset::ISO2022JP => encode_with_encoding(input, ISO_2022_JP),
+        Charset::EUCJP => encode_with_encoding(input, EUC_JP),
+    };
+
+    result.map_err(|unmappables| EncodeError {
+        charset: charset.clone(),
+        unmappables,
+    })
+}
+
+fn encode_ascii(input: &str) -> Result<Vec<u8>, Vec<Unmappable>> {
+    let unmappables: Vec<Unmappable> = input
+        .char_indices()
+        .filter(|(_, c)| !c.is_ascii())
+        .map(|(offset, character)| Unmappable { character, offset })
+        .collect();
+
+    if unmappables.is_empty() {
+        Ok(input.as_bytes().to_vec())
+    } else {
+        Err(unmappables)
+    }
+}
+
+fn encode_with_encoding(
+    input: &str,
+    encoding: &'static Encoding,
+) -> Result<Vec<u8>, Vec<Unmappable>> {
+    let mut encoder = encoding.new_encoder();
+    let mut output = Vec::with_capacity(input.len() + 16);
+    let mut unmappables = Vec::new();
+    let mut total_read = 0;
+
+    loop {
+        let (result, read) = encoder.encode_from_utf8_to_vec_without_replacement(
+            &input[total_read..],
+            &mut output,
+            true,
+        );
+        total_read += read;
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => output.reserve(input.len() - total_read + 16),
+            EncoderResult::Unmappable(character) => unmappables.push(Unmappable {
+                character,
+                offset: total_read - character.len_utf8(),
+            }),
+        }
+    }
+
+    if unmappables.is_empty() {
+        Ok(output)
+    } else {
+        Err(unmappables)
+    }
+}
+
+fn join_ids<T: ToString>(ids: &[T]) -> String {
+    ids.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::parse;
+
+    mod shell_descript {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case =
+                "charset,Shift_JIS\r\ntype,shell\r\n\r\n// comment\r\nname,master\r\nmenu,auto\r\nsakura.seriko.defaultsurface,0,,10\r\n";
+            let shell_descript = parse(case).unwrap();
+            assert_eq!(shell_descript.to_string(), case);
+        }
+
+        #[test]
+        fn success_when_round_trip() {
+            let case = ShellDescript::new(vec![
+                LineContainer::Body(ShellDescriptLine::Charset(Charset::UTF8)),
+                LineContainer::Body(ShellDescriptLine::Type),
+                LineContainer::EmptyLine,
+                LineContainer::CommentLine("//dressup".to_string()),
+                LineContainer::Body(ShellDescriptLine::SakuraBindgroupName(
+                    BindGroupNameProps::new(0, "帽子".to_string(), "麦わら".to_string(), None),
+                )),
+                LineContainer::Body(ShellDescriptLine::CharBindgroupName(
+                    2,
+                    BindGroupNameProps::new(
+                        1,
+                        "帽子".to_string(),
+                        "リボン".to_string(),
+                        Some("ribbon".to_string()),
+                    ),
+                )),
+                LineContainer::Body(ShellDescriptLine::KeroBindoptionGroup(BindOption::new(
+                    3,
+                    "帽子".to_string(),
+                    true,
+                    true,
+                ))),
+                LineContainer::Body(ShellDescriptLine::MenuBackgroundAlignment(
+                    MenuPositionForegroundBackgroundBase::Centerbottom,
+                    Some(MenuPositionForegroundBackgroundRepeat::RepeatY),
+                    Some(MenuPositionForegroundBackgroundRepeat::RepeatX),
+                )),
+                LineContainer::Body(ShellDescriptLine::MenuSidebarAlignment(
+                    MenuPositionSidebarBase::Bottom,
+                    Some(MenuPositionSidebarRepeat::RepeatY),
+                )),
+                LineContainer::Body(ShellDescriptLine::CharMenuitemex(
+                    3,
+                    4,
+                    "hat".to_string(),
+                    BindMenuItem::Line,
+                )),
+                LineContainer::Body(ShellDescriptLine::SerikoZOrder(vec![1, 0, 2])),
+                LineContainer::Body(ShellDescriptLine::KeroDefaultx(-40)),
+            ]);
+            assert_eq!(parse(&case.to_string()).unwrap(), case);
+        }
+
+        #[test]
+        fn success_when_lossless() {
+            use crate::ast::LineSource;
+
+            let case = ShellDescript::with_sources(vec![
+                (
+                    LineContainer::Body(ShellDescriptLine::SakuraDefaultx(-20)),
+                    LineSource::new("sakura.defaultx,-0020".to_string(), Newline::Lf),
+                ),
+                (
+                    LineContainer::Body(ShellDescriptLine::Type),
+                    LineSource::new("type,shell".to_string(), Newline::Eof),
+                ),
+            ]);
+            assert_eq!(case.to_string(), "sakura.defaultx,-0020\ntype,shell");
+        }
+    }
+
+    mod shell_descript_line {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = ShellDescriptLine::CharBindgroupName(
+                1,
+                BindGroupNameProps::new(
+                    5,
+                    "カテゴリ名".to_string(),
+                    "パーツ名".to_string(),
+                    Some("サムネイル名".to_string()),
+                ),
+            );
+            assert_eq!(
+                case.to_string(),
+                "char1.bindgroup5.name,カテゴリ名,パーツ名,サムネイル名"
+            );
+
+            let case = ShellDescriptLine::SakuraBindoptionGroup(BindOption::new(
+                5,
+                "カテゴリ名".to_string(),
+                true,
+                true,
+            ));
+            assert_eq!(
+                case.to_string(),
+                "sakura.bindoption5.group,カテゴリ名,mustselect+multiple"
+            );
+
+            let case = ShellDescriptLine::MenuForegroundAlignment(
+                MenuPositionForegroundBackgroundBase::Lefttop,
+                Some(MenuPositionForegroundBackgroundRepeat::RepeatX),
+                None,
+            );
+            assert_eq!(
+                case.to_string(),
+                "menu.foreground.alignment,lefttop+repeat-x"
+            );
+
+            let case = ShellDescriptLine::MenuHidden;
+            assert_eq!(case.to_string(), "menu,hidden");
+        }
+    }
+
+    mod bind_option {
+        use super::*;
+
+        #[test]
+        fn success_when_round_trip() {
+            for (is_mustselect, is_multiple) in [(true, false), (false, true), (true, true)] {
+                let case = ShellDescript::new(vec![LineContainer::Body(
+                    ShellDescriptLine::SakuraBindoptionGroup(BindOption::new(
+                        0,
+                        "帽子".to_string(),
+                        is_mustselect,
+                        is_multiple,
+                    )),
+                )]);
+                assert_eq!(parse(&case.to_string()).unwrap(), case);
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn failed_when_no_option() {
+            BindOption::new(0, "帽子".to_string(), false, false);
+        }
+
+        #[test]
+        #[should_panic]
+        fn failed_when_last_option_unset() {
+            let mut case = BindOption::new(0, "帽子".to_string(), false, true);
+            case.set_is_multiple(false);
+        }
+    }
+
+    mod key {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = ShellDescriptLine::CharMenuitem(2, 10, BindMenuItem::Id(3));
+            assert_eq!(case.key(), "char2.menuitem10");
+
+            let case = ShellDescriptLine::Type;
+            assert_eq!(case.key(), "type");
+        }
+    }
+
+    mod value {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = ShellDescriptLine::SakuraBindgroupAddid(5, vec![1, 2, 3]);
+            assert_eq!(case.value(), "1,2,3");
+
+            let case = ShellDescriptLine::ReadmeCharset(Charset::ShiftJIS);
+            assert_eq!(case.value(), "Shift_JIS");
+        }
+    }
+
+    mod encode_bytes {
+        use super::*;
+
+        use crate::parse::decode_bytes;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = "charset,Shift_JIS\r\ncraftmanw,うか犬\r\n";
+            let result = encode_bytes(case).unwrap();
+            assert_eq!(result, SHIFT_JIS.encode(case).0.to_vec());
+            assert_eq!(decode_bytes(&result).unwrap(), case);
+
+            let case = "charset,UTF-8\r\ncraftmanw,\u{1F415}\r\n";
+            let result = encode_bytes(case).unwrap();
+            assert_eq!(result, case.as_bytes());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = "charset,Shift_JIS\r\ncraftmanw,\u{1F415}\u{2603}\r\n";
+            let result = encode_bytes(case).unwrap_err();
+            assert_eq!(result.charset(), &Charset::ShiftJIS);
+            assert_eq!(
+                result.unmappables(),
+                &vec![
+                    Unmappable {
+                        character: '\u{1F415}',
+                        offset: 29
+                    },
+                    Unmappable {
+                        character: '\u{2603}',
+                        offset: 33
+                    }
+                ]
+            );
+        }
+    }
+
+    mod write_bytes {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            let case = ShellDescript::new(vec![
+                LineContainer::Body(ShellDescriptLine::Charset(Charset::EUCJP)),
+                LineContainer::Body(ShellDescriptLine::Craftmanw("うか犬".to_string())),
+            ]);
+            let result = write_bytes(&case).unwrap();
+            assert_eq!(result, EUC_JP.encode(&case.to_string()).0.to_vec());
+        }
+
+        #[test]
+        fn failed_when_invalid_str() {
+            let case = ShellDescript::new(vec![
+                LineContainer::Body(ShellDescriptLine::Charset(Charset::ASCII)),
+                LineContainer::Body(ShellDescriptLine::Craftmanw("うか犬".to_string())),
+            ]);
+            let result = write_bytes(&case).unwrap_err();
+            assert_eq!(result.charset(), &Charset::ASCII);
+            assert_eq!(result.unmappables().len(), 3);
+            assert_eq!(result.unmappables()[0].offset(), &25);
+        }
+    }
+
+    mod charset_name {
+        use super::*;
+
+        #[test]
+        fn success_when_valid_str() {
+            for case in [
+                Charset::ASCII,
+                Charset::ShiftJIS,
+                Charset::ISO2022JP,
+                Charset::EUCJP,
+                Charset::UTF8,
+            ] {
+                let line = format!("charset,{}", charset_name(&case));
+                assert_eq!(
+                    parse(&line).unwrap().lines(),
+                    &vec![LineContainer::Body(ShellDescriptLine::Charset(case))]
+                );
+            }
+        }
+    }
+}